@@ -0,0 +1,149 @@
+//! A lossless concrete syntax tree for tools such as formatters and editors
+//! that need every token of the source, including comments and whitespace.
+//!
+//! The tree is split in two layers:
+//!
+//! - `green` nodes are immutable and only know their kind, their children and
+//!   the length of their text. They can be shared between trees, so editing a
+//!   tree only rebuilds the nodes on the path from the edit to the root.
+//! - `red` nodes are cheap cursors over a green tree that add parent links and
+//!   the offset of each node in the source.
+//!
+//! The `nodes` module layers typed views such as `VarDecl` or `BinaryExpr`
+//! over red nodes. Printing any node gives back its source text exactly, so a
+//! refactoring can replace part of a tree and print the result without
+//! disturbing the formatting of anything else.
+
+pub mod green;
+pub mod nodes;
+pub mod red;
+
+/// The kind of a token or node in the concrete syntax tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    // Punctuation and operators.
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Colon,
+    Comma,
+    Period,
+    Minus,
+    Plus,
+    Semicolon,
+    Slash,
+    Star,
+    Equal,
+    Greater,
+    Less,
+    Bang,
+    Arrow,
+    BangEqual,
+    EqualEqual,
+    GreaterEqual,
+    LessEqual,
+
+    // Literals.
+    Identifier,
+    String,
+    StringStart,
+    StringMiddle,
+    StringEnd,
+    RawString,
+    MultilineString,
+    Float,
+    Int,
+
+    // Keywords.
+    And,
+    Or,
+    Not,
+    Break,
+    Continue,
+    If,
+    Else,
+    True,
+    False,
+    Null,
+    Fn,
+    For,
+    Var,
+    Const,
+    Return,
+    While,
+
+    // Trivia.
+    Comment,
+    BlockComment,
+    DocComment,
+    Whitespace,
+    Newline,
+
+    /// Input that could not be scanned as a token.
+    ErrorToken,
+
+    // Nodes.
+    /// The root of a tree parsed from a whole source file.
+    SourceFile,
+    VarDecl,
+    FnDecl,
+    /// The parenthesized parameters of a function.
+    ParamList,
+    /// A parameter name and its optional type annotation.
+    Param,
+    /// The `-> type` after a function's parameters.
+    RetType,
+    Block,
+    IfStmt,
+    WhileStmt,
+    ForStmt,
+    BreakStmt,
+    ContinueStmt,
+    ReturnStmt,
+    ExprStmt,
+    /// A name being declared, such as a variable or parameter name.
+    Name,
+    /// A name used as an expression.
+    NameRef,
+    Literal,
+    AssignExpr,
+    ParenExpr,
+    UnaryExpr,
+    BinaryExpr,
+    /// An `and` or `or` expression.
+    LogicalExpr,
+    CallExpr,
+    /// The parenthesized arguments of a call.
+    ArgList,
+    FnExpr,
+    InterpolationExpr,
+    /// A type written as a name, such as `int` or `null`.
+    NameType,
+    /// A function type such as `fn(int) -> bool`.
+    FnType,
+    /// Tokens that are out of place, kept so that no source text is lost.
+    Error,
+}
+
+impl SyntaxKind {
+    /// True for whitespace and comments, which may appear between any two
+    /// tokens.
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Comment
+                | SyntaxKind::BlockComment
+                | SyntaxKind::DocComment
+                | SyntaxKind::Whitespace
+                | SyntaxKind::Newline
+        )
+    }
+
+    /// True for the kinds of tokens, false for the kinds of nodes.
+    pub fn is_token(&self) -> bool {
+        (*self as u8) < (SyntaxKind::SourceFile as u8)
+    }
+}
@@ -0,0 +1,213 @@
+//! Immutable syntax tree nodes that are shared between trees.
+
+use std::{fmt, rc::Rc};
+
+use crate::cst::SyntaxKind;
+
+/// A token and its source text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    text: String,
+}
+
+impl GreenToken {
+    pub fn new(kind: SyntaxKind, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Length of the token's text in bytes.
+    pub fn text_len(&self) -> usize {
+        self.text.len()
+    }
+}
+
+impl fmt::Display for GreenToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// A node with its child nodes and tokens in source order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    text_len: usize,
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    pub fn new(kind: SyntaxKind, children: impl IntoIterator<Item = GreenElement>) -> Self {
+        let children: Vec<_> = children.into_iter().collect();
+        let text_len = children.iter().map(GreenElement::text_len).sum();
+
+        Self {
+            kind,
+            text_len,
+            children,
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// Length of the text of every token in the node, in bytes.
+    pub fn text_len(&self) -> usize {
+        self.text_len
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    /// A copy of the node with the child at `index` replaced. The other
+    /// children are shared with `self`.
+    pub fn replace_child(&self, index: usize, child: GreenElement) -> GreenNode {
+        self.splice_children(index..index + 1, [child])
+    }
+
+    /// A copy of the node with the children in `range` replaced by
+    /// `replacement`, which can be used to insert or remove children too.
+    pub fn splice_children(
+        &self,
+        range: std::ops::Range<usize>,
+        replacement: impl IntoIterator<Item = GreenElement>,
+    ) -> GreenNode {
+        let mut children = self.children.clone();
+        children.splice(range, replacement);
+        GreenNode::new(self.kind, children)
+    }
+}
+
+impl fmt::Display for GreenNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for child in &self.children {
+            write!(f, "{child}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A child of a green node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>),
+}
+
+impl GreenElement {
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            GreenElement::Node(node) => node.kind(),
+            GreenElement::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn text_len(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.text_len(),
+            GreenElement::Token(token) => token.text_len(),
+        }
+    }
+}
+
+impl From<GreenNode> for GreenElement {
+    fn from(node: GreenNode) -> Self {
+        GreenElement::Node(Rc::new(node))
+    }
+}
+
+impl From<GreenToken> for GreenElement {
+    fn from(token: GreenToken) -> Self {
+        GreenElement::Token(Rc::new(token))
+    }
+}
+
+impl fmt::Display for GreenElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GreenElement::Node(node) => node.fmt(f),
+            GreenElement::Token(token) => token.fmt(f),
+        }
+    }
+}
+
+/// Builds a green tree from the top down, as a parser reads the source.
+#[derive(Debug, Default)]
+pub struct GreenNodeBuilder {
+    /// Nodes that have been started but not finished, with the index in
+    /// `children` of their first child.
+    parents: Vec<(SyntaxKind, usize)>,
+    /// Children of every unfinished node, innermost last.
+    children: Vec<GreenElement>,
+}
+
+/// A position in a `GreenNodeBuilder` where a node can be started after its
+/// first children have already been added, such as the left operand of a
+/// binary expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint(usize);
+
+impl GreenNodeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a token to the current node.
+    pub fn token(&mut self, kind: SyntaxKind, text: &str) {
+        self.children.push(GreenToken::new(kind, text).into());
+    }
+
+    /// Starts a node, which becomes the current node until `finish_node`.
+    pub fn start_node(&mut self, kind: SyntaxKind) {
+        self.parents.push((kind, self.children.len()));
+    }
+
+    /// Finishes the current node and adds it to its parent.
+    pub fn finish_node(&mut self) {
+        let (kind, first_child) = self
+            .parents
+            .pop()
+            .expect("finish_node called without a matching start_node");
+        let children = self.children.split_off(first_child);
+        self.children.push(GreenNode::new(kind, children).into());
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.children.len())
+    }
+
+    /// Starts a node that adopts everything added since `checkpoint` as its
+    /// first children.
+    pub fn start_node_at(&mut self, checkpoint: Checkpoint, kind: SyntaxKind) {
+        let Checkpoint(first_child) = checkpoint;
+        assert!(
+            first_child <= self.children.len()
+                && self.parents.last().is_none_or(|(_, i)| *i <= first_child),
+            "checkpoint is no longer valid"
+        );
+        self.parents.push((kind, first_child));
+    }
+
+    /// Returns the finished tree. Every node must have been finished and the
+    /// root node must be the only element left.
+    pub fn finish(mut self) -> GreenNode {
+        assert!(self.parents.is_empty(), "unfinished nodes in the builder");
+        match self.children.pop() {
+            Some(GreenElement::Node(root)) if self.children.is_empty() => Rc::unwrap_or_clone(root),
+            _ => panic!("the builder must hold exactly one root node"),
+        }
+    }
+}
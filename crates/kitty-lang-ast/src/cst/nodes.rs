@@ -0,0 +1,531 @@
+//! Typed views over syntax nodes. Each view wraps a `SyntaxNode` of one kind
+//! and has accessors for its parts, which return `None` when the part is
+//! missing because the source has a syntax error.
+
+use crate::cst::{
+    SyntaxKind,
+    red::{SyntaxElement, SyntaxNode, SyntaxToken},
+};
+
+/// A typed view of a syntax node.
+pub trait AstNode: Sized {
+    fn can_cast(kind: SyntaxKind) -> bool;
+
+    /// Wraps `node` if it is of a kind the view supports.
+    fn cast(node: SyntaxNode) -> Option<Self>;
+
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+macro_rules! ast_node {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name(SyntaxNode);
+
+        impl AstNode for $name {
+            fn can_cast(kind: SyntaxKind) -> bool {
+                kind == SyntaxKind::$name
+            }
+
+            fn cast(node: SyntaxNode) -> Option<Self> {
+                Self::can_cast(node.kind()).then(|| Self(node))
+            }
+
+            fn syntax(&self) -> &SyntaxNode {
+                &self.0
+            }
+        }
+    };
+}
+
+macro_rules! ast_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum $name {
+            $($variant($variant),)*
+        }
+
+        impl AstNode for $name {
+            fn can_cast(kind: SyntaxKind) -> bool {
+                matches!(kind, $(SyntaxKind::$variant)|*)
+            }
+
+            fn cast(node: SyntaxNode) -> Option<Self> {
+                match node.kind() {
+                    $(SyntaxKind::$variant => Some($name::$variant($variant(node))),)*
+                    _ => None,
+                }
+            }
+
+            fn syntax(&self) -> &SyntaxNode {
+                match self {
+                    $($name::$variant(node) => node.syntax(),)*
+                }
+            }
+        }
+    };
+}
+
+ast_node!(
+    /// The root of a tree parsed from a whole source file.
+    SourceFile
+);
+ast_node!(VarDecl);
+ast_node!(FnDecl);
+ast_node!(ParamList);
+ast_node!(Param);
+ast_node!(RetType);
+ast_node!(Block);
+ast_node!(IfStmt);
+ast_node!(WhileStmt);
+ast_node!(ForStmt);
+ast_node!(BreakStmt);
+ast_node!(ContinueStmt);
+ast_node!(ReturnStmt);
+ast_node!(ExprStmt);
+ast_node!(
+    /// A name being declared.
+    Name
+);
+ast_node!(
+    /// A name used as an expression.
+    NameRef
+);
+ast_node!(Literal);
+ast_node!(AssignExpr);
+ast_node!(ParenExpr);
+ast_node!(UnaryExpr);
+ast_node!(BinaryExpr);
+ast_node!(
+    /// An `and` or `or` expression.
+    LogicalExpr
+);
+ast_node!(CallExpr);
+ast_node!(ArgList);
+ast_node!(FnExpr);
+ast_node!(InterpolationExpr);
+ast_node!(NameType);
+ast_node!(FnType);
+
+ast_enum!(
+    /// Any declaration or statement.
+    Stmt {
+        VarDecl,
+        FnDecl,
+        Block,
+        IfStmt,
+        WhileStmt,
+        ForStmt,
+        BreakStmt,
+        ContinueStmt,
+        ReturnStmt,
+        ExprStmt,
+    }
+);
+
+ast_enum!(
+    /// Any type annotation.
+    Type { NameType, FnType }
+);
+
+ast_enum!(
+    /// Any expression.
+    Expr {
+        Literal,
+        NameRef,
+        AssignExpr,
+        ParenExpr,
+        UnaryExpr,
+        BinaryExpr,
+        LogicalExpr,
+        CallExpr,
+        FnExpr,
+        InterpolationExpr,
+    }
+);
+
+/// The first child of `node` that `N` can view.
+fn child<N: AstNode>(node: &SyntaxNode) -> Option<N> {
+    node.children().find_map(N::cast)
+}
+
+fn children<N: AstNode>(node: &SyntaxNode) -> impl Iterator<Item = N> + use<N> {
+    node.children().filter_map(N::cast)
+}
+
+/// The first token of `node` of the given kind, not counting tokens in child
+/// nodes.
+fn token(node: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+    node.children_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|token| token.kind() == kind)
+}
+
+/// The first token of `node` that is not trivia, not counting tokens in child
+/// nodes.
+fn first_significant_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.children_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|token| !token.kind().is_trivia())
+}
+
+/// The children of `node` that `N` can view, split at the first token of the
+/// given kind into those before it and those after it.
+fn children_around<N: AstNode>(node: &SyntaxNode, kind: SyntaxKind) -> (Vec<N>, Vec<N>) {
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    let mut seen = false;
+
+    for element in node.children_with_tokens() {
+        match element {
+            SyntaxElement::Token(token) if token.kind() == kind => seen = true,
+            SyntaxElement::Node(node) => {
+                if let Some(child) = N::cast(node) {
+                    if seen {
+                        after.push(child);
+                    } else {
+                        before.push(child);
+                    }
+                }
+            }
+            SyntaxElement::Token(_) => {}
+        }
+    }
+
+    (before, after)
+}
+
+/// The operands on each side of the operator of a binary or logical
+/// expression.
+fn operands(node: &SyntaxNode) -> (Option<Expr>, Option<Expr>) {
+    let Some(op) = first_significant_token(node) else {
+        return (None, None);
+    };
+    let (lhs, rhs) = children_around(node, op.kind());
+
+    (lhs.into_iter().next(), rhs.into_iter().next())
+}
+
+impl SourceFile {
+    pub fn stmts(&self) -> impl Iterator<Item = Stmt> + use<> {
+        children(&self.0)
+    }
+}
+
+impl VarDecl {
+    /// The `var` or `const` keyword.
+    pub fn keyword(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Var).or_else(|| token(&self.0, SyntaxKind::Const))
+    }
+
+    pub fn name(&self) -> Option<Name> {
+        child(&self.0)
+    }
+
+    /// The type annotation after the name.
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.0)
+    }
+
+    pub fn initializer(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    /// The `///` comments before the declaration.
+    pub fn doc_comments(&self) -> impl Iterator<Item = SyntaxToken> + use<> {
+        doc_comments(&self.0)
+    }
+}
+
+impl FnDecl {
+    pub fn name(&self) -> Option<Name> {
+        child(&self.0)
+    }
+
+    pub fn param_list(&self) -> Option<ParamList> {
+        child(&self.0)
+    }
+
+    pub fn ret_type(&self) -> Option<RetType> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<Block> {
+        child(&self.0)
+    }
+
+    /// The `///` comments before the declaration.
+    pub fn doc_comments(&self) -> impl Iterator<Item = SyntaxToken> + use<> {
+        doc_comments(&self.0)
+    }
+}
+
+fn doc_comments(node: &SyntaxNode) -> impl Iterator<Item = SyntaxToken> + use<> {
+    node.children_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .take_while(|token| token.kind().is_trivia())
+        .filter(|token| token.kind() == SyntaxKind::DocComment)
+}
+
+impl ParamList {
+    pub fn params(&self) -> impl Iterator<Item = Param> + use<> {
+        children(&self.0)
+    }
+}
+
+impl Param {
+    pub fn name(&self) -> Option<Name> {
+        child(&self.0)
+    }
+
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.0)
+    }
+}
+
+impl RetType {
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.0)
+    }
+}
+
+impl Block {
+    pub fn stmts(&self) -> impl Iterator<Item = Stmt> + use<> {
+        children(&self.0)
+    }
+}
+
+impl IfStmt {
+    pub fn condition(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    pub fn then_branch(&self) -> Option<Stmt> {
+        children_around(&self.0, SyntaxKind::Else)
+            .0
+            .into_iter()
+            .next()
+    }
+
+    pub fn else_branch(&self) -> Option<Stmt> {
+        children_around(&self.0, SyntaxKind::Else)
+            .1
+            .into_iter()
+            .next()
+    }
+}
+
+impl WhileStmt {
+    pub fn condition(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<Stmt> {
+        child(&self.0)
+    }
+}
+
+impl ForStmt {
+    /// The declaration or expression statement before the first `;`.
+    pub fn initializer(&self) -> Option<Stmt> {
+        self.clauses().initializer
+    }
+
+    pub fn condition(&self) -> Option<Expr> {
+        self.clauses().condition
+    }
+
+    pub fn increment(&self) -> Option<Expr> {
+        self.clauses().increment
+    }
+
+    pub fn body(&self) -> Option<Stmt> {
+        self.clauses().body
+    }
+
+    /// Finds the parts of the loop from where they are relative to its `;`
+    /// and `)` tokens. The `;` ending a non-empty initializer belongs to the
+    /// initializer's statement node rather than to the loop.
+    fn clauses(&self) -> ForClauses {
+        let mut clauses = ForClauses::default();
+        let mut section = 0;
+
+        for element in self.0.children_with_tokens() {
+            match element {
+                SyntaxElement::Token(token) => match token.kind() {
+                    SyntaxKind::Semicolon => section += 1,
+                    SyntaxKind::RightParen => section = 3,
+                    _ => {}
+                },
+                SyntaxElement::Node(node) => match section {
+                    0 => {
+                        clauses.initializer = Stmt::cast(node);
+                        section = 1;
+                    }
+                    1 => clauses.condition = Expr::cast(node),
+                    2 => clauses.increment = Expr::cast(node),
+                    _ => clauses.body = Stmt::cast(node),
+                },
+            }
+        }
+
+        clauses
+    }
+}
+
+#[derive(Default)]
+struct ForClauses {
+    initializer: Option<Stmt>,
+    condition: Option<Expr>,
+    increment: Option<Expr>,
+    body: Option<Stmt>,
+}
+
+impl ReturnStmt {
+    pub fn value(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+impl ExprStmt {
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+impl Name {
+    pub fn ident(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Identifier)
+    }
+}
+
+impl NameRef {
+    pub fn ident(&self) -> Option<SyntaxToken> {
+        token(&self.0, SyntaxKind::Identifier)
+    }
+}
+
+impl Literal {
+    /// The literal's token, such as an `Int` or a `String`.
+    pub fn token(&self) -> Option<SyntaxToken> {
+        first_significant_token(&self.0)
+    }
+}
+
+impl AssignExpr {
+    pub fn target(&self) -> Option<Expr> {
+        children_around(&self.0, SyntaxKind::Equal)
+            .0
+            .into_iter()
+            .next()
+    }
+
+    pub fn value(&self) -> Option<Expr> {
+        children_around(&self.0, SyntaxKind::Equal)
+            .1
+            .into_iter()
+            .next()
+    }
+}
+
+impl ParenExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+impl UnaryExpr {
+    /// The `-` or `not` operator.
+    pub fn op(&self) -> Option<SyntaxToken> {
+        first_significant_token(&self.0)
+    }
+
+    pub fn operand(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+}
+
+impl BinaryExpr {
+    pub fn op(&self) -> Option<SyntaxToken> {
+        first_significant_token(&self.0)
+    }
+
+    pub fn lhs(&self) -> Option<Expr> {
+        operands(&self.0).0
+    }
+
+    pub fn rhs(&self) -> Option<Expr> {
+        operands(&self.0).1
+    }
+}
+
+impl LogicalExpr {
+    /// The `and` or `or` operator.
+    pub fn op(&self) -> Option<SyntaxToken> {
+        first_significant_token(&self.0)
+    }
+
+    pub fn lhs(&self) -> Option<Expr> {
+        operands(&self.0).0
+    }
+
+    pub fn rhs(&self) -> Option<Expr> {
+        operands(&self.0).1
+    }
+}
+
+impl CallExpr {
+    pub fn callee(&self) -> Option<Expr> {
+        child(&self.0)
+    }
+
+    pub fn arg_list(&self) -> Option<ArgList> {
+        child(&self.0)
+    }
+}
+
+impl ArgList {
+    pub fn args(&self) -> impl Iterator<Item = Expr> + use<> {
+        children(&self.0)
+    }
+}
+
+impl FnExpr {
+    pub fn param_list(&self) -> Option<ParamList> {
+        child(&self.0)
+    }
+
+    pub fn ret_type(&self) -> Option<RetType> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<Block> {
+        child(&self.0)
+    }
+}
+
+impl InterpolationExpr {
+    /// The expressions between the braces, in source order.
+    pub fn exprs(&self) -> impl Iterator<Item = Expr> + use<> {
+        children(&self.0)
+    }
+}
+
+impl NameType {
+    /// The identifier or `null` keyword naming the type.
+    pub fn token(&self) -> Option<SyntaxToken> {
+        first_significant_token(&self.0)
+    }
+}
+
+impl FnType {
+    pub fn params(&self) -> impl Iterator<Item = Type> + use<> {
+        children(&self.0)
+    }
+
+    pub fn ret_type(&self) -> Option<RetType> {
+        child(&self.0)
+    }
+}
@@ -0,0 +1,244 @@
+//! Cursors over a green tree that know their parent and position.
+
+use std::{fmt, ops::Range, rc::Rc};
+
+use crate::cst::{
+    SyntaxKind,
+    green::{GreenElement, GreenNode, GreenToken},
+};
+
+/// A node in a syntax tree. Cloning is cheap and nodes are created on demand
+/// while walking the tree, so two `SyntaxNode`s are equal when they are the
+/// same node of the same tree.
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+struct NodeData {
+    green: Rc<GreenNode>,
+    parent: Option<SyntaxNode>,
+    /// Index of the node among its parent's children.
+    index: usize,
+    /// Byte offset of the node's text in the source.
+    offset: usize,
+}
+
+impl SyntaxNode {
+    pub fn new_root(green: GreenNode) -> Self {
+        Self(Rc::new(NodeData {
+            green: Rc::new(green),
+            parent: None,
+            index: 0,
+            offset: 0,
+        }))
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind()
+    }
+
+    pub fn green(&self) -> &GreenNode {
+        &self.0.green
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.clone()
+    }
+
+    /// The node and each of its ancestors, ending with the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> + use<> {
+        std::iter::successors(Some(self.clone()), SyntaxNode::parent)
+    }
+
+    /// Range of bytes of the source covered by the node.
+    pub fn text_range(&self) -> Range<usize> {
+        self.0.offset..self.0.offset + self.0.green.text_len()
+    }
+
+    pub fn children_with_tokens(&self) -> impl Iterator<Item = SyntaxElement> + use<> {
+        let parent = self.clone();
+        let mut offset = self.0.offset;
+
+        (0..self.0.green.children().len()).map(move |index| {
+            let child = &parent.0.green.children()[index];
+            let child_offset = offset;
+            offset += child.text_len();
+
+            match child {
+                GreenElement::Node(green) => SyntaxElement::Node(SyntaxNode(Rc::new(NodeData {
+                    green: green.clone(),
+                    parent: Some(parent.clone()),
+                    index,
+                    offset: child_offset,
+                }))),
+                GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                    green: green.clone(),
+                    parent: parent.clone(),
+                    index,
+                    offset: child_offset,
+                }),
+            }
+        })
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode> + use<> {
+        self.children_with_tokens()
+            .filter_map(SyntaxElement::into_node)
+    }
+
+    /// The node and every node below it, in source order.
+    pub fn descendants(&self) -> impl Iterator<Item = SyntaxNode> + use<> {
+        let mut stack = vec![self.clone()];
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let start = stack.len();
+            stack.extend(node.children());
+            stack[start..].reverse();
+            Some(node)
+        })
+    }
+
+    /// Every token below the node, including trivia, in source order.
+    pub fn tokens(&self) -> impl Iterator<Item = SyntaxToken> + use<> {
+        self.descendants().flat_map(|node| {
+            node.children_with_tokens()
+                .filter_map(SyntaxElement::into_token)
+        })
+    }
+
+    /// The source text of the node.
+    pub fn text(&self) -> String {
+        self.0.green.to_string()
+    }
+
+    /// Returns the root of a new tree where this node is replaced by
+    /// `replacement`. Only the ancestors of the node are rebuilt, everything
+    /// else is shared with the current tree.
+    pub fn replace_with(&self, replacement: GreenNode) -> GreenNode {
+        match &self.0.parent {
+            Some(parent) => parent.replace_with(
+                parent
+                    .green()
+                    .replace_child(self.0.index, replacement.into()),
+            ),
+            None => replacement,
+        }
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}@{:?}", self.kind(), self.text_range())
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.green.fmt(f)
+    }
+}
+
+/// A token in a syntax tree.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SyntaxToken {
+    green: Rc<GreenToken>,
+    parent: SyntaxNode,
+    index: usize,
+    offset: usize,
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind()
+    }
+
+    pub fn text(&self) -> &str {
+        self.green.text()
+    }
+
+    pub fn green(&self) -> &GreenToken {
+        &self.green
+    }
+
+    pub fn parent(&self) -> SyntaxNode {
+        self.parent.clone()
+    }
+
+    /// Range of bytes of the source covered by the token.
+    pub fn text_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.text_len()
+    }
+
+    /// Returns the root of a new tree where this token is replaced by
+    /// `replacement`.
+    pub fn replace_with(&self, replacement: GreenToken) -> GreenNode {
+        self.parent.replace_with(
+            self.parent
+                .green()
+                .replace_child(self.index, replacement.into()),
+        )
+    }
+}
+
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?}@{:?} {:?}",
+            self.kind(),
+            self.text_range(),
+            self.text()
+        )
+    }
+}
+
+impl fmt::Display for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
+/// A child of a syntax node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            SyntaxElement::Node(node) => node.kind(),
+            SyntaxElement::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn text_range(&self) -> Range<usize> {
+        match self {
+            SyntaxElement::Node(node) => node.text_range(),
+            SyntaxElement::Token(token) => token.text_range(),
+        }
+    }
+
+    pub fn into_node(self) -> Option<SyntaxNode> {
+        match self {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        }
+    }
+
+    pub fn into_token(self) -> Option<SyntaxToken> {
+        match self {
+            SyntaxElement::Node(_) => None,
+            SyntaxElement::Token(token) => Some(token),
+        }
+    }
+}
@@ -1 +1,6 @@
-
+pub mod cst;
+pub mod expr;
+pub mod sexpr;
+pub mod span;
+pub mod stmt;
+pub mod types;
@@ -0,0 +1,243 @@
+//! Renders syntax trees as S-expressions, eg `(+ 1 (* 2 3))`. Names with a
+//! type annotation are written as `name:type`.
+
+use crate::{
+    expr::{Expr, ExprKind, Literal},
+    stmt::{Function, Stmt, StmtKind},
+    types::TypeExpr,
+};
+
+/// Renders an expression on a single line.
+pub fn expr(expr: &Expr) -> String {
+    let mut printer = Printer::new(false);
+    printer.expr(expr);
+    printer.out
+}
+
+/// Renders a statement on a single line.
+pub fn stmt(stmt: &Stmt) -> String {
+    let mut printer = Printer::new(false);
+    printer.stmt(stmt);
+    printer.out
+}
+
+/// Renders each statement in a program on its own line, with nested
+/// statements on indented lines below the statement that contains them.
+pub fn program(stmts: &[Stmt]) -> String {
+    let mut printer = Printer::new(true);
+
+    for stmt in stmts {
+        printer.stmt(stmt);
+        printer.out.push('\n');
+    }
+
+    printer.out
+}
+
+struct Printer {
+    out: String,
+    /// Put nested statements on their own lines.
+    pretty: bool,
+    indent: usize,
+}
+
+impl Printer {
+    fn new(pretty: bool) -> Self {
+        Self {
+            out: String::new(),
+            pretty,
+            indent: 0,
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Literal(literal) => match literal {
+                Literal::Int(v) => self.out.push_str(&v.to_string()),
+                Literal::Float(v) => self.out.push_str(&format!("{v:?}")),
+                Literal::String(v) => self.out.push_str(&format!("{v:?}")),
+                Literal::Bool(v) => self.out.push_str(&v.to_string()),
+                Literal::Null => self.out.push_str("null"),
+            },
+            ExprKind::Variable(name) => self.out.push_str(name),
+            ExprKind::Assign { target, value } => {
+                self.open("=");
+                self.atom(&target.name);
+                self.child_expr(value);
+                self.close();
+            }
+            ExprKind::Grouping(expr) => {
+                self.open("group");
+                self.child_expr(expr);
+                self.close();
+            }
+            ExprKind::Call { callee, args } => {
+                self.open("call");
+                self.child_expr(callee);
+                for arg in args {
+                    self.child_expr(arg);
+                }
+                self.close();
+            }
+            ExprKind::Function(function) => self.function(function),
+            ExprKind::Interpolation(parts) => {
+                self.open("str");
+                for part in parts {
+                    self.child_expr(part);
+                }
+                self.close();
+            }
+            ExprKind::Unary { op, expr } => {
+                self.open(op.as_str());
+                self.child_expr(expr);
+                self.close();
+            }
+            ExprKind::Binary { op, left, right } => {
+                self.open(op.as_str());
+                self.child_expr(left);
+                self.child_expr(right);
+                self.close();
+            }
+            ExprKind::Logical { op, left, right } => {
+                self.open(op.as_str());
+                self.child_expr(left);
+                self.child_expr(right);
+                self.close();
+            }
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::Expression(expr) => {
+                self.open("expr");
+                self.child_expr(expr);
+                self.close();
+            }
+            StmtKind::Var(decl) => {
+                self.open(decl.mutability.as_str());
+                self.atom(&typed_name(&decl.name.name, decl.ty.as_ref()));
+                self.optional_expr(decl.initializer.as_ref());
+                self.close();
+            }
+            StmtKind::Fn(function) => self.function(function),
+            StmtKind::Block(stmts) => {
+                self.open("block");
+                for stmt in stmts {
+                    self.child_stmt(stmt);
+                }
+                self.close();
+            }
+            StmtKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.open("if");
+                self.child_expr(condition);
+                self.child_stmt(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.child_stmt(else_branch);
+                }
+                self.close();
+            }
+            StmtKind::While { condition, body } => {
+                self.open("while");
+                self.child_expr(condition);
+                self.child_stmt(body);
+                self.close();
+            }
+            StmtKind::For {
+                initializer,
+                condition,
+                increment,
+                body,
+            } => {
+                self.open("for");
+                match initializer {
+                    Some(initializer) => self.child_stmt(initializer),
+                    None => self.atom("_"),
+                }
+                self.optional_expr(condition.as_ref());
+                self.optional_expr(increment.as_ref());
+                self.child_stmt(body);
+                self.close();
+            }
+            StmtKind::Break => self.out.push_str("(break)"),
+            StmtKind::Continue => self.out.push_str("(continue)"),
+            StmtKind::Return(value) => {
+                self.open("return");
+                self.optional_expr(value.as_ref());
+                self.close();
+            }
+        }
+    }
+
+    fn function(&mut self, function: &Function) {
+        self.open("fn");
+        self.atom(function.name.as_ref().map_or("_", |n| n.name.as_str()));
+
+        let params = function
+            .params
+            .iter()
+            .map(|p| typed_name(&p.name.name, p.ty.as_ref()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.atom(&format!("({params})"));
+
+        if let Some(return_type) = &function.return_type {
+            self.atom(&format!("-> {return_type}"));
+        }
+
+        for stmt in &function.body {
+            self.child_stmt(stmt);
+        }
+        self.close();
+    }
+
+    fn open(&mut self, name: &str) {
+        self.out.push('(');
+        self.out.push_str(name);
+        self.indent += 1;
+    }
+
+    fn close(&mut self) {
+        self.out.push(')');
+        self.indent -= 1;
+    }
+
+    fn atom(&mut self, text: &str) {
+        self.out.push(' ');
+        self.out.push_str(text);
+    }
+
+    fn child_expr(&mut self, expr: &Expr) {
+        self.out.push(' ');
+        self.expr(expr);
+    }
+
+    fn optional_expr(&mut self, expr: Option<&Expr>) {
+        match expr {
+            Some(expr) => self.child_expr(expr),
+            None => self.atom("_"),
+        }
+    }
+
+    fn child_stmt(&mut self, stmt: &Stmt) {
+        if self.pretty {
+            self.out.push('\n');
+            self.out.push_str(&"  ".repeat(self.indent));
+        } else {
+            self.out.push(' ');
+        }
+
+        self.stmt(stmt);
+    }
+}
+
+fn typed_name(name: &str, ty: Option<&TypeExpr>) -> String {
+    match ty {
+        Some(ty) => format!("{name}:{ty}"),
+        None => name.to_string(),
+    }
+}
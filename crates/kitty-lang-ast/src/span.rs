@@ -0,0 +1,30 @@
+/// A region of source text that a syntax node was parsed from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Index of the first character of the span in the source str.
+    pub index: usize,
+    /// Number of characters in the span.
+    pub length: usize,
+}
+
+impl Span {
+    pub fn new(index: usize, length: usize) -> Self {
+        Self { index, length }
+    }
+
+    /// Index one past the last character in the span.
+    pub fn end(&self) -> usize {
+        self.index + self.length
+    }
+
+    /// Returns the smallest span that covers both `self` and `other`.
+    pub fn to(&self, other: Span) -> Span {
+        let index = self.index.min(other.index);
+        let end = self.end().max(other.end());
+
+        Span {
+            index,
+            length: end - index,
+        }
+    }
+}
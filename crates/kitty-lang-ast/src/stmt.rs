@@ -0,0 +1,102 @@
+use std::rc::Rc;
+
+use crate::{
+    expr::{Expr, Ident},
+    span::Span,
+    types::TypeExpr,
+};
+
+/// A statement node and the region of source it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+impl Stmt {
+    pub fn new(kind: StmtKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StmtKind {
+    /// An expression evaluated for its side effects, eg `x = 2;`.
+    Expression(Expr),
+    /// A `var` or `const` declaration.
+    Var(VarDecl),
+    /// A named `fn name(a, b) { ... }` declaration.
+    Fn(Rc<Function>),
+    /// A `{ ... }` block introducing a new scope.
+    Block(Vec<Stmt>),
+    If {
+        condition: Expr,
+        then_branch: Box<Stmt>,
+        else_branch: Option<Box<Stmt>>,
+    },
+    While {
+        condition: Expr,
+        body: Box<Stmt>,
+    },
+    /// A C style `for (initializer; condition; increment) body` loop. Each of
+    /// the clauses in the parentheses may be omitted.
+    For {
+        initializer: Option<Box<Stmt>>,
+        condition: Option<Expr>,
+        increment: Option<Expr>,
+        body: Box<Stmt>,
+    },
+    Break,
+    Continue,
+    Return(Option<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarDecl {
+    pub name: Ident,
+    pub mutability: Mutability,
+    /// The type written after the name, eg `int` in `var x: int = 1;`.
+    pub ty: Option<TypeExpr>,
+    /// Initial value for the variable. Always present for constants.
+    pub initializer: Option<Expr>,
+    /// Text of the `///` doc comments before the declaration.
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutability {
+    /// Declared with `var` and can be reassigned.
+    Var,
+    /// Declared with `const` and cannot be reassigned.
+    Const,
+}
+
+impl Mutability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Mutability::Var => "var",
+            Mutability::Const => "const",
+        }
+    }
+}
+
+/// The parameters and body of a named function declaration or an anonymous
+/// `fn` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    /// Name of the function, or `None` for anonymous functions.
+    pub name: Option<Ident>,
+    pub params: Vec<Param>,
+    /// The type written after `->`, eg `string` in `fn f() -> string`.
+    pub return_type: Option<TypeExpr>,
+    pub body: Vec<Stmt>,
+    /// Text of the `///` doc comments before a named function declaration.
+    pub doc: Option<String>,
+}
+
+/// A function parameter and its optional type annotation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: Ident,
+    pub ty: Option<TypeExpr>,
+}
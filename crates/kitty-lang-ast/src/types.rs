@@ -0,0 +1,53 @@
+use std::fmt;
+
+use crate::span::Span;
+
+/// A type annotation and the region of source it was parsed from, such as the
+/// `int` in `var x: int = 1;`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeExpr {
+    pub kind: TypeExprKind,
+    pub span: Span,
+}
+
+impl TypeExpr {
+    pub fn new(kind: TypeExprKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeExprKind {
+    /// A type named by an identifier such as `int`, or `null`. Names are not
+    /// checked by the parser, so this may name a type that does not exist.
+    Name(String),
+    /// A function type such as `fn(int, float) -> string`. A missing return
+    /// type means the function returns `null`.
+    Function {
+        params: Vec<TypeExpr>,
+        ret: Option<Box<TypeExpr>>,
+    },
+}
+
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TypeExprKind::Name(name) => f.write_str(name),
+            TypeExprKind::Function { params, ret } => {
+                f.write_str("fn(")?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{param}")?;
+                }
+                f.write_str(")")?;
+
+                match ret {
+                    Some(ret) => write!(f, " -> {ret}"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,716 @@
+//! Parsing source into a lossless concrete syntax tree, see
+//! `kitty_lang_ast::cst`.
+
+use kitty_lang_ast::{
+    cst::{
+        SyntaxKind,
+        green::{Checkpoint, GreenNodeBuilder},
+        nodes::{AstNode, SourceFile},
+        red::SyntaxNode,
+    },
+    span::Span,
+};
+
+use crate::{
+    parser::ParseError,
+    scanner::{Lexeme, Scanner},
+    tokens::Token,
+};
+
+/// Parses source into a concrete syntax tree that keeps every token, including
+/// whitespace and comments, so that printing the tree gives back the source
+/// exactly.
+///
+/// The grammar is the same as `Parser`'s. Unlike `Parser` this never gives up
+/// on a syntax error: out of place tokens are wrapped in `SyntaxKind::Error`
+/// nodes and parsing continues, so a tree is built for any input. Only syntax
+/// errors are reported. Checks such as whether numbers are in range or
+/// `break` is inside a loop are left to `Parser`.
+///
+/// Whitespace and comments between two tokens are put in the innermost node
+/// that is open when the second token is reached, except that `///` doc
+/// comments before a declaration are put in the declaration's node.
+pub struct CstParser<'a> {
+    source: &'a str,
+    /// Every lexeme including whitespace and comments.
+    lexemes: Vec<Lexeme>,
+    /// Index of the first lexeme not yet added to the tree.
+    current: usize,
+    /// Number of significant lexemes added to the tree, used to check that
+    /// parsing makes progress.
+    bumped: usize,
+    builder: GreenNodeBuilder,
+    errors: Vec<ParseError>,
+}
+
+/// Tokens that end or start a statement. An unexpected one of these is left
+/// for an enclosing rule to deal with rather than being wrapped in an error
+/// node by the expression parser.
+const RECOVERY_TOKENS: &[Token] = &[
+    Token::Semicolon,
+    Token::RightParen,
+    Token::RightBrace,
+    Token::Var,
+    Token::Const,
+    Token::If,
+    Token::While,
+    Token::For,
+    Token::Break,
+    Token::Continue,
+    Token::Return,
+];
+
+impl<'a> CstParser<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            lexemes: Scanner::new(source).with_trivia().collect(),
+            current: 0,
+            bumped: 0,
+            builder: GreenNodeBuilder::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Parses the whole source, returning the tree and any syntax errors.
+    pub fn parse(mut self) -> (SourceFile, Vec<ParseError>) {
+        self.builder.start_node(SyntaxKind::SourceFile);
+
+        while self.peek().is_some() {
+            self.declaration_with_progress();
+        }
+
+        self.flush_trivia(self.lexemes.len());
+        self.builder.finish_node();
+
+        let root = SyntaxNode::new_root(self.builder.finish());
+        let source_file = SourceFile::cast(root).expect("the root is a source file");
+        (source_file, self.errors)
+    }
+
+    /// Parses a declaration, and if that did not consume anything wraps the
+    /// next lexeme in an error node so that the caller's loop ends.
+    fn declaration_with_progress(&mut self) {
+        let bumped = self.bumped;
+        self.declaration();
+
+        if self.bumped == bumped {
+            self.error_node("a declaration or statement");
+        }
+    }
+
+    fn declaration(&mut self) {
+        match self.peek_token() {
+            Some(Token::Var | Token::Const) => self.var_declaration(),
+            Some(Token::Fn) if self.peek_next_token() == Some(Token::Identifier) => {
+                self.fn_declaration()
+            }
+            Some(Token::Semicolon | Token::RightParen | Token::RightBrace) => {
+                self.error_node("a declaration or statement")
+            }
+            _ => self.statement(),
+        }
+    }
+
+    fn fn_declaration(&mut self) {
+        self.start_declaration(SyntaxKind::FnDecl);
+        self.bump();
+        self.name("a function name");
+        self.function();
+        self.builder.finish_node();
+    }
+
+    fn param_list(&mut self) {
+        self.start_node(SyntaxKind::ParamList);
+
+        if self.expect(Token::LeftParen, "'(' before function parameters")
+            && !self.eat(Token::RightParen)
+        {
+            loop {
+                self.start_node(SyntaxKind::Param);
+                self.name("a parameter name");
+                self.type_annotation();
+                self.builder.finish_node();
+
+                if !self.eat(Token::Comma) {
+                    break;
+                }
+            }
+
+            self.expect(Token::RightParen, "')' after function parameters");
+        }
+
+        self.builder.finish_node();
+    }
+
+    /// Parses the parameters, return type and body of a function.
+    fn function(&mut self) {
+        self.param_list();
+
+        if self.peek_token() == Some(Token::Arrow) {
+            self.ret_type();
+        }
+
+        self.block();
+    }
+
+    fn ret_type(&mut self) {
+        self.start_node(SyntaxKind::RetType);
+        self.bump();
+        self.type_ref();
+        self.builder.finish_node();
+    }
+
+    /// Parses an optional `: type` after a name.
+    fn type_annotation(&mut self) {
+        if self.eat(Token::Colon) {
+            self.type_ref();
+        }
+    }
+
+    fn type_ref(&mut self) {
+        match self.peek_token() {
+            Some(Token::Identifier | Token::Null) => {
+                self.start_node(SyntaxKind::NameType);
+                self.bump();
+                self.builder.finish_node();
+            }
+            Some(Token::Fn) => {
+                self.start_node(SyntaxKind::FnType);
+                self.bump();
+
+                if self.expect(Token::LeftParen, "'(' after 'fn'") && !self.eat(Token::RightParen) {
+                    loop {
+                        self.type_ref();
+
+                        if !self.eat(Token::Comma) {
+                            break;
+                        }
+                    }
+
+                    self.expect(Token::RightParen, "')' after parameter types");
+                }
+
+                if self.peek_token() == Some(Token::Arrow) {
+                    self.ret_type();
+                }
+
+                self.builder.finish_node();
+            }
+            _ => self.error("a type"),
+        }
+    }
+
+    fn var_declaration(&mut self) {
+        self.start_declaration(SyntaxKind::VarDecl);
+        let is_const = self.bump() == Token::Const;
+        self.name("a variable name");
+        self.type_annotation();
+
+        if self.eat(Token::Equal) {
+            self.expression();
+        } else if is_const {
+            self.expect(Token::Equal, "'=' after constant name");
+        }
+
+        self.expect(Token::Semicolon, "';' after variable declaration");
+        self.builder.finish_node();
+    }
+
+    fn name(&mut self, expected: &'static str) {
+        match self.peek() {
+            Some(lexeme) if lexeme.token == Token::Identifier => {
+                self.start_node(SyntaxKind::Name);
+                self.bump();
+                self.builder.finish_node();
+            }
+            Some(lexeme) if lexeme.token.is_keyword() => {
+                self.errors.push(ParseError::KeywordAsIdentifier {
+                    expected,
+                    keyword: lexeme.token.keyword().unwrap_or_default(),
+                    span: lexeme.span(),
+                });
+                self.start_node(SyntaxKind::Error);
+                self.bump();
+                self.builder.finish_node();
+            }
+            _ => self.error(expected),
+        }
+    }
+
+    fn statement(&mut self) {
+        match self.peek_token() {
+            Some(Token::LeftBrace) => self.block(),
+            Some(Token::If) => self.if_statement(),
+            Some(Token::While) => self.while_statement(),
+            Some(Token::For) => self.for_statement(),
+            Some(Token::Break) => {
+                self.keyword_statement(SyntaxKind::BreakStmt, "';' after 'break'")
+            }
+            Some(Token::Continue) => {
+                self.keyword_statement(SyntaxKind::ContinueStmt, "';' after 'continue'")
+            }
+            Some(Token::Return) => self.return_statement(),
+            _ => self.expression_statement(),
+        }
+    }
+
+    fn block(&mut self) {
+        self.start_node(SyntaxKind::Block);
+
+        if self.expect(Token::LeftBrace, "'{'") {
+            loop {
+                match self.peek_token() {
+                    Some(Token::RightBrace) => {
+                        self.bump();
+                        break;
+                    }
+                    Some(_) => self.declaration_with_progress(),
+                    None => {
+                        self.error("'}' after block");
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.builder.finish_node();
+    }
+
+    fn if_statement(&mut self) {
+        self.start_node(SyntaxKind::IfStmt);
+        self.bump();
+        self.expect(Token::LeftParen, "'(' after 'if'");
+        self.expression();
+        self.expect(Token::RightParen, "')' after if condition");
+        self.statement();
+
+        if self.eat(Token::Else) {
+            self.statement();
+        }
+
+        self.builder.finish_node();
+    }
+
+    fn while_statement(&mut self) {
+        self.start_node(SyntaxKind::WhileStmt);
+        self.bump();
+        self.expect(Token::LeftParen, "'(' after 'while'");
+        self.expression();
+        self.expect(Token::RightParen, "')' after while condition");
+        self.statement();
+        self.builder.finish_node();
+    }
+
+    fn for_statement(&mut self) {
+        self.start_node(SyntaxKind::ForStmt);
+        self.bump();
+        self.expect(Token::LeftParen, "'(' after 'for'");
+
+        match self.peek_token() {
+            Some(Token::Semicolon) => {
+                self.bump();
+            }
+            Some(Token::Var | Token::Const) => self.var_declaration(),
+            _ => self.expression_statement(),
+        }
+
+        if self.peek_token() != Some(Token::Semicolon) {
+            self.expression();
+        }
+        self.expect(Token::Semicolon, "';' after loop condition");
+
+        if self.peek_token() != Some(Token::RightParen) {
+            self.expression();
+        }
+        self.expect(Token::RightParen, "')' after for clauses");
+
+        self.statement();
+        self.builder.finish_node();
+    }
+
+    /// Parses `break;` or `continue;`.
+    fn keyword_statement(&mut self, kind: SyntaxKind, expected: &'static str) {
+        self.start_node(kind);
+        self.bump();
+        self.expect(Token::Semicolon, expected);
+        self.builder.finish_node();
+    }
+
+    fn return_statement(&mut self) {
+        self.start_node(SyntaxKind::ReturnStmt);
+        self.bump();
+
+        if self.peek_token() != Some(Token::Semicolon) {
+            self.expression();
+        }
+
+        self.expect(Token::Semicolon, "';' after return value");
+        self.builder.finish_node();
+    }
+
+    fn expression_statement(&mut self) {
+        self.start_node(SyntaxKind::ExprStmt);
+        self.expression();
+        self.expect(Token::Semicolon, "';' after expression");
+        self.builder.finish_node();
+    }
+
+    fn expression(&mut self) {
+        self.assignment();
+    }
+
+    /// Returns the kind of node that was parsed.
+    fn assignment(&mut self) -> SyntaxKind {
+        let checkpoint = self.checkpoint();
+        let start = self.peek().map(Lexeme::span);
+        let target = self.or();
+
+        if self.peek_token() != Some(Token::Equal) {
+            return target;
+        }
+
+        if target != SyntaxKind::NameRef
+            && let Some(start) = start
+        {
+            self.errors.push(ParseError::InvalidAssignmentTarget {
+                span: start.to(self.previous_span()),
+            });
+        }
+
+        self.builder
+            .start_node_at(checkpoint, SyntaxKind::AssignExpr);
+        self.bump();
+        self.assignment();
+        self.builder.finish_node();
+        SyntaxKind::AssignExpr
+    }
+
+    fn or(&mut self) -> SyntaxKind {
+        self.binary(SyntaxKind::LogicalExpr, &[Token::Or], Self::and)
+    }
+
+    fn and(&mut self) -> SyntaxKind {
+        self.binary(SyntaxKind::LogicalExpr, &[Token::And], Self::equality)
+    }
+
+    fn equality(&mut self) -> SyntaxKind {
+        self.binary(
+            SyntaxKind::BinaryExpr,
+            &[Token::EqualEqual, Token::BangEqual],
+            Self::comparison,
+        )
+    }
+
+    fn comparison(&mut self) -> SyntaxKind {
+        self.binary(
+            SyntaxKind::BinaryExpr,
+            &[
+                Token::Less,
+                Token::LessEqual,
+                Token::Greater,
+                Token::GreaterEqual,
+            ],
+            Self::term,
+        )
+    }
+
+    fn term(&mut self) -> SyntaxKind {
+        self.binary(
+            SyntaxKind::BinaryExpr,
+            &[Token::Plus, Token::Minus],
+            Self::factor,
+        )
+    }
+
+    fn factor(&mut self) -> SyntaxKind {
+        self.binary(
+            SyntaxKind::BinaryExpr,
+            &[Token::Star, Token::Slash],
+            Self::unary,
+        )
+    }
+
+    /// Parses a left associative run of operands separated by any of `ops`.
+    fn binary(
+        &mut self,
+        kind: SyntaxKind,
+        ops: &[Token],
+        operand: fn(&mut Self) -> SyntaxKind,
+    ) -> SyntaxKind {
+        let checkpoint = self.checkpoint();
+        let mut parsed = operand(self);
+
+        while self.peek_token().is_some_and(|t| ops.contains(&t)) {
+            self.builder.start_node_at(checkpoint, kind);
+            self.bump();
+            operand(self);
+            self.builder.finish_node();
+            parsed = kind;
+        }
+
+        parsed
+    }
+
+    fn unary(&mut self) -> SyntaxKind {
+        if !matches!(self.peek_token(), Some(Token::Minus | Token::Not)) {
+            return self.call();
+        }
+
+        self.start_node(SyntaxKind::UnaryExpr);
+        self.bump();
+        self.unary();
+        self.builder.finish_node();
+        SyntaxKind::UnaryExpr
+    }
+
+    fn call(&mut self) -> SyntaxKind {
+        let checkpoint = self.checkpoint();
+        let mut parsed = self.primary();
+
+        while self.peek_token() == Some(Token::LeftParen) {
+            self.builder.start_node_at(checkpoint, SyntaxKind::CallExpr);
+            self.arg_list();
+            self.builder.finish_node();
+            parsed = SyntaxKind::CallExpr;
+        }
+
+        parsed
+    }
+
+    fn arg_list(&mut self) {
+        self.start_node(SyntaxKind::ArgList);
+        self.bump();
+
+        if self.peek_token() != Some(Token::RightParen) {
+            loop {
+                self.expression();
+
+                if !self.eat(Token::Comma) {
+                    break;
+                }
+            }
+        }
+
+        self.expect(Token::RightParen, "')' after arguments");
+        self.builder.finish_node();
+    }
+
+    fn primary(&mut self) -> SyntaxKind {
+        let kind = match self.peek_token() {
+            Some(
+                Token::True
+                | Token::False
+                | Token::Null
+                | Token::Int
+                | Token::Float
+                | Token::String
+                | Token::RawString
+                | Token::MultilineString,
+            ) => SyntaxKind::Literal,
+            Some(Token::Identifier) => SyntaxKind::NameRef,
+            Some(Token::LeftParen) => {
+                self.start_node(SyntaxKind::ParenExpr);
+                self.bump();
+                self.expression();
+                self.expect(Token::RightParen, "')' after expression");
+                self.builder.finish_node();
+                return SyntaxKind::ParenExpr;
+            }
+            Some(Token::Fn) => {
+                self.start_node(SyntaxKind::FnExpr);
+                self.bump();
+                self.function();
+                self.builder.finish_node();
+                return SyntaxKind::FnExpr;
+            }
+            Some(Token::StringStart) => return self.interpolation(),
+            Some(token) if !RECOVERY_TOKENS.contains(&token) => {
+                self.error_node("an expression");
+                return SyntaxKind::Error;
+            }
+            _ => {
+                self.error("an expression");
+                return SyntaxKind::Error;
+            }
+        };
+
+        self.start_node(kind);
+        self.bump();
+        self.builder.finish_node();
+        kind
+    }
+
+    fn interpolation(&mut self) -> SyntaxKind {
+        self.start_node(SyntaxKind::InterpolationExpr);
+        self.bump();
+
+        loop {
+            self.expression();
+
+            match self.peek_token() {
+                Some(Token::StringMiddle) => {
+                    self.bump();
+                }
+                Some(Token::StringEnd) => {
+                    self.bump();
+                    break;
+                }
+                _ => {
+                    self.error("'}' after interpolated expression");
+                    break;
+                }
+            }
+        }
+
+        self.builder.finish_node();
+        SyntaxKind::InterpolationExpr
+    }
+}
+
+impl CstParser<'_> {
+    /// Index of the next significant lexeme, skipping whitespace and
+    /// comments.
+    fn next_significant(&self, from: usize) -> Option<usize> {
+        (from..self.lexemes.len()).find(|&i| !is_trivia(self.lexemes[i].token))
+    }
+
+    fn peek(&self) -> Option<&Lexeme> {
+        self.next_significant(self.current)
+            .map(|i| &self.lexemes[i])
+    }
+
+    fn peek_token(&self) -> Option<Token> {
+        self.peek().map(|l| l.token)
+    }
+
+    fn peek_next_token(&self) -> Option<Token> {
+        let next = self.next_significant(self.current)?;
+        self.next_significant(next + 1)
+            .map(|i| self.lexemes[i].token)
+    }
+
+    /// Span of the last significant lexeme added to the tree.
+    fn previous_span(&self) -> Span {
+        self.lexemes[..self.current]
+            .iter()
+            .rev()
+            .find(|l| !is_trivia(l.token))
+            .map_or(Span::default(), Lexeme::span)
+    }
+
+    /// Adds the trivia before the next significant lexeme and the lexeme
+    /// itself to the current node, returning the lexeme's token.
+    fn bump(&mut self) -> Token {
+        let next = self
+            .next_significant(self.current)
+            .expect("bump called at the end of input");
+        self.flush_trivia(next);
+
+        let lexeme = &self.lexemes[next];
+        self.builder
+            .token(lexeme.token.syntax_kind(), lexeme.text(self.source));
+        self.current = next + 1;
+        self.bumped += 1;
+        lexeme.token
+    }
+
+    /// Adds the lexemes before `end` to the current node.
+    fn flush_trivia(&mut self, end: usize) {
+        for lexeme in &self.lexemes[self.current..end] {
+            self.builder
+                .token(lexeme.token.syntax_kind(), lexeme.text(self.source));
+        }
+        self.current = self.current.max(end);
+    }
+
+    /// Starts a node after adding pending trivia to the current node, so that
+    /// nodes begin with a significant token.
+    fn start_node(&mut self, kind: SyntaxKind) {
+        if let Some(next) = self.next_significant(self.current) {
+            self.flush_trivia(next);
+        }
+        self.builder.start_node(kind);
+    }
+
+    /// Starts a declaration node, which includes the doc comments before it.
+    fn start_declaration(&mut self, kind: SyntaxKind) {
+        let next = self
+            .next_significant(self.current)
+            .unwrap_or(self.lexemes.len());
+        let docs = (self.current..next).find(|&i| self.lexemes[i].token == Token::DocComment);
+
+        self.flush_trivia(docs.unwrap_or(next));
+        self.builder.start_node(kind);
+    }
+
+    fn checkpoint(&mut self) -> Checkpoint {
+        if let Some(next) = self.next_significant(self.current) {
+            self.flush_trivia(next);
+        }
+        self.builder.checkpoint()
+    }
+
+    fn eat(&mut self, token: Token) -> bool {
+        if self.peek_token() == Some(token) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Adds the next lexeme if it is `token`, otherwise reports an error.
+    fn expect(&mut self, token: Token, expected: &'static str) -> bool {
+        if self.eat(token) {
+            true
+        } else {
+            self.error(expected);
+            false
+        }
+    }
+
+    /// Reports that the next lexeme was not what was expected. Errors at the
+    /// same place as the previous error are dropped, as they are usually
+    /// caused by it.
+    fn error(&mut self, expected: &'static str) {
+        let error = match self.peek() {
+            Some(lexeme) => match lexeme.token {
+                Token::Invalid(reason) => ParseError::InvalidToken {
+                    reason,
+                    text: lexeme.text(self.source).to_string(),
+                    span: lexeme.span(),
+                },
+                found => ParseError::UnexpectedToken {
+                    expected,
+                    found,
+                    span: lexeme.span(),
+                },
+            },
+            None => ParseError::UnexpectedEof {
+                expected,
+                span: Span::new(self.source.chars().count(), 0),
+            },
+        };
+
+        if self.errors.last().map(ParseError::span) != Some(error.span()) {
+            self.errors.push(error);
+        }
+    }
+
+    /// Reports an error and wraps the next lexeme in an error node.
+    fn error_node(&mut self, expected: &'static str) {
+        self.error(expected);
+
+        if self.peek().is_some() {
+            self.start_node(SyntaxKind::Error);
+            self.bump();
+            self.builder.finish_node();
+        }
+    }
+}
+
+/// Lexemes that the grammar skips over.
+fn is_trivia(token: Token) -> bool {
+    token.syntax_kind().is_trivia()
+}
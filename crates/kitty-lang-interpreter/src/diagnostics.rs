@@ -0,0 +1,312 @@
+//! Errors and warnings about kitty source code, and rendering them rustc style
+//! with the offending source line and a caret underline:
+//!
+//! ```text
+//! error: unterminated string
+//!  --> main.kitty:2:9
+//!   |
+//! 2 | var s = "cat;
+//!   |         ^^^^^
+//!   |
+//!   = help: add a closing `"` to end the string
+//! ```
+
+use std::fmt::Write;
+
+use kitty_lang_ast::span::Span;
+
+use crate::{
+    interpreter::RuntimeError,
+    literals::unescape_string,
+    parser::ParseError,
+    scanner::Lexeme,
+    source::SourceFile,
+    tokens::{InvalidTokenReason, Token},
+    typeck::{TYPE_NAMES, Type, TypeError},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A problem found in a source file, pointing at the span of source that
+/// caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    /// Short text printed next to the underlined span.
+    pub label: Option<String>,
+    /// Suggestions for fixing the problem, printed after the source snippet.
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, message, span)
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Warning, message, span)
+    }
+
+    fn new(severity: Severity, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            span,
+            label: None,
+            help: Vec::new(),
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    /// Renders the diagnostic without color.
+    pub fn render(&self, source: &SourceFile) -> String {
+        Renderer::plain().render(source, self)
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(error: &ParseError) -> Self {
+        let diagnostic = Diagnostic::error(error.to_string(), error.span());
+
+        match error {
+            ParseError::InvalidToken { reason, text, span } => invalid_token(*reason, text, *span),
+            ParseError::UnexpectedToken { found, .. } => {
+                diagnostic.with_label(format!("unexpected {found}"))
+            }
+            ParseError::KeywordAsIdentifier { keyword, .. } => diagnostic.with_help(format!(
+                "`{keyword}` is a keyword and cannot be used as a name"
+            )),
+            ParseError::InvalidAssignmentTarget { .. } => {
+                diagnostic.with_help("only variables can be assigned to")
+            }
+            ParseError::NumberOutOfRange {
+                token: Token::Float,
+                ..
+            } => diagnostic.with_help("floats must be finite (at most ~1.8e308)"),
+            ParseError::NumberOutOfRange { .. } => diagnostic.with_help(format!(
+                "ints must be between {} and {}",
+                i64::MIN,
+                i64::MAX
+            )),
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic {
+    fn from(error: &RuntimeError) -> Self {
+        let diagnostic = Diagnostic::error(error.to_string(), error.span());
+
+        match error {
+            RuntimeError::AssignToConstant { .. } => {
+                diagnostic.with_help("declare it with `var` if it needs to change")
+            }
+            RuntimeError::ExpectedBool { .. } => diagnostic
+                .with_label("this must be a bool")
+                .with_help("values are not implicitly converted to bool, compare it instead"),
+            RuntimeError::StackOverflow { .. } => {
+                diagnostic.with_help("check for recursion that never reaches a base case")
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&TypeError> for Diagnostic {
+    fn from(error: &TypeError) -> Self {
+        let diagnostic = Diagnostic::error(error.to_string(), error.span());
+
+        match error {
+            TypeError::Mismatch {
+                expected: Type::Bool,
+                ..
+            } => diagnostic
+                .with_label("this must be a bool")
+                .with_help("values are not implicitly converted to bool, compare it instead"),
+            TypeError::Mismatch { found, .. } => diagnostic.with_label(format!("this is {found}")),
+            TypeError::UnknownType { .. } => diagnostic.with_help(format!(
+                "the types are {} and function types such as `fn(int) -> string`",
+                TYPE_NAMES.join(", ")
+            )),
+            _ => diagnostic,
+        }
+    }
+}
+
+impl Lexeme {
+    /// A diagnostic explaining why the lexeme is invalid, or `None` if it is a
+    /// valid token.
+    pub fn diagnostic(&self, source: &str) -> Option<Diagnostic> {
+        let Token::Invalid(reason) = self.token else {
+            return None;
+        };
+
+        Some(invalid_token(reason, self.text(source), self.span()))
+    }
+}
+
+/// A diagnostic for an invalid token with source text `text` at `span`.
+fn invalid_token(reason: InvalidTokenReason, text: &str, span: Span) -> Diagnostic {
+    let span = match reason {
+        InvalidTokenReason::InvalidEscape => invalid_escape_span(text, span),
+        _ => span,
+    };
+    let diagnostic = Diagnostic::error(reason.describe(text), span);
+
+    match reason.help() {
+        Some(help) => diagnostic.with_help(help),
+        None => diagnostic,
+    }
+}
+
+/// Narrows the span of a string with an invalid escape to the escape itself,
+/// so the caret points at the `\q` rather than the whole string.
+fn invalid_escape_span(text: &str, span: Span) -> Span {
+    // String lexemes start with a `"` or `}` delimiter, which is never part of
+    // an escape. The closing delimiter can be unescaped with the contents.
+    match unescape_string(&text[1..]) {
+        Ok(_) => span,
+        Err(escape) => Span::new(
+            span.index + 1 + text[1..1 + escape.byte_index].chars().count(),
+            escape.text.chars().count(),
+        ),
+    }
+}
+
+/// Formats diagnostics for display in a terminal, optionally using ANSI
+/// colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Renderer {
+    color: bool,
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+
+impl Renderer {
+    pub fn plain() -> Self {
+        Self { color: false }
+    }
+
+    pub fn colored() -> Self {
+        Self { color: true }
+    }
+
+    pub fn render(&self, source: &SourceFile, diagnostic: &Diagnostic) -> String {
+        let mut out = String::new();
+        let severity_style = match diagnostic.severity {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+        };
+
+        let start = source.location(diagnostic.span.index);
+        let end = source.location(diagnostic.span.end());
+        let line_number = start.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        let _ = writeln!(
+            out,
+            "{}{}{}{}: {}{}",
+            self.style(severity_style),
+            diagnostic.severity.as_str(),
+            self.style(RESET),
+            self.style(BOLD),
+            diagnostic.message,
+            self.style(RESET),
+        );
+        let _ = writeln!(
+            out,
+            "{gutter}{}-->{} {}:{start}",
+            self.style(BLUE),
+            self.style(RESET),
+            source.name()
+        );
+
+        if let Some(line_text) = source.line_text(start.line) {
+            let bar = format!("{}|{}", self.style(BLUE), self.style(RESET));
+            let _ = writeln!(out, "{gutter} {bar}");
+            let _ = writeln!(
+                out,
+                "{}{line_number}{} {bar} {line_text}",
+                self.style(BLUE),
+                self.style(RESET)
+            );
+
+            // Underline up to the end of the span, or the end of the first
+            // line when the span continues onto later lines. Tabs are kept in
+            // the padding so the carets line up with the text above.
+            let line_length = line_text.chars().count();
+            let underline_end = if end.line == start.line {
+                end.column
+            } else {
+                line_length + 1
+            };
+            let underline_length = underline_end.saturating_sub(start.column).max(1);
+            let padding: String = line_text
+                .chars()
+                .take(start.column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+
+            let _ = write!(
+                out,
+                "{gutter} {bar} {padding}{}{}",
+                self.style(severity_style),
+                "^".repeat(underline_length)
+            );
+            if let Some(label) = &diagnostic.label {
+                let _ = write!(out, " {label}");
+            }
+            let _ = writeln!(out, "{}", self.style(RESET));
+
+            if !diagnostic.help.is_empty() {
+                let _ = writeln!(out, "{gutter} {bar}");
+            }
+        }
+
+        for help in &diagnostic.help {
+            let _ = writeln!(
+                out,
+                "{gutter} {}={} {}help{}: {help}",
+                self.style(BLUE),
+                self.style(RESET),
+                self.style(CYAN),
+                self.style(RESET)
+            );
+        }
+
+        out
+    }
+
+    fn style(&self, code: &'static str) -> &'static str {
+        if self.color { code } else { "" }
+    }
+}
@@ -0,0 +1,519 @@
+//! Reprinting kitty source in a canonical style.
+//!
+//! Formatting works on the lossless syntax tree from `CstParser` so that
+//! comments survive. Comments between statements keep their place: a comment
+//! on the same line as the end of a statement stays there, and a comment on
+//! its own line stays on its own line. A single blank line between statements
+//! is kept, longer runs of blank lines are shortened to one.
+//!
+//! A statement with a comment somewhere inside it, such as between the
+//! operands of an expression, is printed as written rather than risk moving
+//! the comment somewhere it does not belong.
+
+use kitty_lang_ast::cst::{
+    SyntaxKind,
+    nodes::{AstNode, Block, Expr, ForStmt, IfStmt, ParamList, RetType, Stmt, Type},
+    red::{SyntaxElement, SyntaxNode, SyntaxToken},
+};
+
+use crate::{cst_parser::CstParser, parser::ParseError};
+
+/// Formats kitty source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Formatter {
+    max_width: usize,
+    indent_width: usize,
+}
+
+impl Formatter {
+    pub fn new() -> Self {
+        Self {
+            max_width: 100,
+            indent_width: 4,
+        }
+    }
+
+    /// Sets the number of columns that a line should fit in. Call arguments
+    /// that do not fit are put on a line each.
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// Sets the number of spaces in each level of indentation.
+    pub fn with_indent_width(mut self, indent_width: usize) -> Self {
+        self.indent_width = indent_width;
+        self
+    }
+
+    /// Returns `source` in the canonical style, or the syntax errors that
+    /// stopped it from being formatted.
+    pub fn format(&self, source: &str) -> Result<String, Vec<ParseError>> {
+        let (file, errors) = CstParser::new(source).parse();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut printer = Printer::new(self, 0, 0);
+        printer.items(file.syntax().children_with_tokens(), false);
+
+        if !printer.out.is_empty() {
+            printer.out.push('\n');
+        }
+
+        Ok(printer.out)
+    }
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formats `source` with the default settings.
+pub fn format(source: &str) -> Result<String, Vec<ParseError>> {
+    Formatter::new().format(source)
+}
+
+struct Printer<'a> {
+    formatter: &'a Formatter,
+    out: String,
+    /// Current level of indentation.
+    indent: usize,
+    /// Column that the first line of `out` starts at, for printers that
+    /// format part of a line to measure it.
+    start_column: usize,
+    /// Whether call arguments that do not fit on a line are wrapped.
+    wrap: bool,
+}
+
+impl<'a> Printer<'a> {
+    fn new(formatter: &'a Formatter, indent: usize, start_column: usize) -> Self {
+        Self {
+            formatter,
+            out: String::new(),
+            indent,
+            start_column,
+            wrap: true,
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        self.out.push_str(text);
+    }
+
+    /// Starts a new line at the current indentation.
+    fn newline(&mut self) {
+        self.out.push('\n');
+        self.write_indent();
+    }
+
+    fn write_indent(&mut self) {
+        let width = self.indent * self.formatter.indent_width;
+        self.out.extend(std::iter::repeat_n(' ', width));
+    }
+
+    fn column(&self) -> usize {
+        match self.out.rfind('\n') {
+            Some(i) => self.out[i + 1..].chars().count(),
+            None => self.start_column + self.out.chars().count(),
+        }
+    }
+
+    /// Prints the statements and comments of a source file or block, each on
+    /// its own line. `after_open` is true when the current line already has
+    /// text that a trailing comment can follow, such as a block's `{`.
+    fn items(&mut self, elements: impl Iterator<Item = SyntaxElement>, after_open: bool) {
+        let mut newlines = 0;
+        let mut first = true;
+        let mut can_trail = after_open;
+
+        for element in elements {
+            match element {
+                SyntaxElement::Token(token) => match token.kind() {
+                    SyntaxKind::Newline => newlines += 1,
+                    SyntaxKind::Comment | SyntaxKind::BlockComment | SyntaxKind::DocComment => {
+                        if newlines == 0 && can_trail {
+                            self.write(" ");
+                        } else {
+                            self.item_break(first, newlines);
+                        }
+
+                        self.write(token.text().trim_end());
+                        first = false;
+                        newlines = 0;
+                        can_trail = true;
+                    }
+                    _ => {}
+                },
+                SyntaxElement::Node(node) => {
+                    self.item_break(first, newlines);
+                    self.stmt(&node);
+                    first = false;
+                    newlines = 0;
+                    can_trail = true;
+                }
+            }
+        }
+    }
+
+    /// Starts the line for the next item, keeping one blank line if there
+    /// were `newlines` of them before it in the source.
+    fn item_break(&mut self, first: bool, newlines: usize) {
+        if !self.out.is_empty() {
+            if newlines > 1 && !first {
+                self.out.push('\n');
+            }
+            self.out.push('\n');
+        }
+        self.write_indent();
+    }
+
+    fn stmt(&mut self, node: &SyntaxNode) {
+        if node.kind() != SyntaxKind::Block && has_inner_comments(node) {
+            self.write(node.text().trim_end());
+            return;
+        }
+
+        let Some(stmt) = Stmt::cast(node.clone()) else {
+            self.write(node.text().trim());
+            return;
+        };
+
+        match stmt {
+            Stmt::VarDecl(decl) => {
+                self.doc_comments(decl.doc_comments());
+                let keyword = decl.keyword();
+                self.write(keyword.as_ref().map_or("var", |k| k.text()));
+                self.write(" ");
+                self.name(decl.name().map(|n| n.syntax().clone()));
+                self.type_annotation(decl.ty());
+
+                if let Some(initializer) = decl.initializer() {
+                    self.write(" = ");
+                    self.expr(&initializer);
+                }
+
+                self.write(";");
+            }
+            Stmt::FnDecl(decl) => {
+                self.doc_comments(decl.doc_comments());
+                self.write("fn ");
+                self.name(decl.name().map(|n| n.syntax().clone()));
+                self.function(decl.param_list(), decl.ret_type(), decl.body());
+            }
+            Stmt::Block(block) => self.block(block.syntax()),
+            Stmt::IfStmt(stmt) => self.if_stmt(&stmt),
+            Stmt::WhileStmt(stmt) => {
+                self.write("while (");
+                self.optional_expr(stmt.condition());
+                self.write(")");
+                self.body(stmt.body());
+            }
+            Stmt::ForStmt(stmt) => self.for_stmt(&stmt),
+            Stmt::BreakStmt(_) => self.write("break;"),
+            Stmt::ContinueStmt(_) => self.write("continue;"),
+            Stmt::ReturnStmt(stmt) => {
+                self.write("return");
+
+                if let Some(value) = stmt.value() {
+                    self.write(" ");
+                    self.expr(&value);
+                }
+
+                self.write(";");
+            }
+            Stmt::ExprStmt(stmt) => {
+                self.optional_expr(stmt.expr());
+                self.write(";");
+            }
+        }
+    }
+
+    /// Prints a declaration's doc comments, each on its own line.
+    fn doc_comments(&mut self, comments: impl Iterator<Item = SyntaxToken>) {
+        for comment in comments {
+            self.write(comment.text().trim_end());
+            self.newline();
+        }
+    }
+
+    fn name(&mut self, name: Option<SyntaxNode>) {
+        if let Some(name) = name {
+            self.write(name.text().trim());
+        }
+    }
+
+    fn function(
+        &mut self,
+        params: Option<ParamList>,
+        ret_type: Option<RetType>,
+        body: Option<Block>,
+    ) {
+        self.write("(");
+
+        for (i, param) in params.iter().flat_map(ParamList::params).enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+
+            self.name(param.name().map(|n| n.syntax().clone()));
+            self.type_annotation(param.ty());
+        }
+
+        self.write(")");
+        self.ret_type(ret_type);
+        self.write(" ");
+
+        if let Some(body) = body {
+            self.block(body.syntax());
+        }
+    }
+
+    fn type_annotation(&mut self, ty: Option<Type>) {
+        if let Some(ty) = ty {
+            self.write(": ");
+            self.ty(&ty);
+        }
+    }
+
+    fn ret_type(&mut self, ret_type: Option<RetType>) {
+        if let Some(ty) = ret_type.and_then(|r| r.ty()) {
+            self.write(" -> ");
+            self.ty(&ty);
+        }
+    }
+
+    fn ty(&mut self, ty: &Type) {
+        match ty {
+            Type::NameType(name) => self.write(name.syntax().text().trim()),
+            Type::FnType(function) => {
+                self.write("fn(");
+
+                for (i, param) in function.params().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.ty(&param);
+                }
+
+                self.write(")");
+                self.ret_type(function.ret_type());
+            }
+        }
+    }
+
+    fn block(&mut self, block: &SyntaxNode) {
+        self.write("{");
+        let start = self.out.len();
+
+        self.indent += 1;
+        self.items(
+            block
+                .children_with_tokens()
+                .filter(|e| !matches!(e.kind(), SyntaxKind::LeftBrace | SyntaxKind::RightBrace)),
+            true,
+        );
+        self.indent -= 1;
+
+        if self.out.len() > start {
+            self.newline();
+        }
+        self.write("}");
+    }
+
+    /// Prints the body of an `if`, `while` or `for`. Blocks start on the same
+    /// line as the condition and other statements follow it after a space.
+    fn body(&mut self, body: Option<Stmt>) {
+        if let Some(body) = body {
+            self.write(" ");
+            self.stmt(body.syntax());
+        }
+    }
+
+    fn if_stmt(&mut self, stmt: &IfStmt) {
+        self.write("if (");
+        self.optional_expr(stmt.condition());
+        self.write(")");
+
+        let then_branch = stmt.then_branch();
+        let then_is_block = matches!(then_branch, Some(Stmt::Block(_)));
+        self.body(then_branch);
+
+        if let Some(else_branch) = stmt.else_branch() {
+            if then_is_block {
+                self.write(" ");
+            } else {
+                self.newline();
+            }
+
+            self.write("else");
+            self.body(Some(else_branch));
+        }
+    }
+
+    fn for_stmt(&mut self, stmt: &ForStmt) {
+        self.write("for (");
+
+        match stmt.initializer() {
+            Some(initializer) => self.stmt(initializer.syntax()),
+            None => self.write(";"),
+        }
+
+        if let Some(condition) = stmt.condition() {
+            self.write(" ");
+            self.expr(&condition);
+        }
+        self.write(";");
+
+        if let Some(increment) = stmt.increment() {
+            self.write(" ");
+            self.expr(&increment);
+        }
+        self.write(")");
+
+        self.body(stmt.body());
+    }
+
+    fn optional_expr(&mut self, expr: Option<Expr>) {
+        if let Some(expr) = expr {
+            self.expr(&expr);
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(_) | Expr::NameRef(_) => self.write(expr.syntax().text().trim()),
+            Expr::AssignExpr(assign) => {
+                self.optional_expr(assign.target());
+                self.write(" = ");
+                self.optional_expr(assign.value());
+            }
+            Expr::ParenExpr(paren) => {
+                self.write("(");
+                self.optional_expr(paren.expr());
+                self.write(")");
+            }
+            Expr::UnaryExpr(unary) => {
+                let op = unary.op();
+                let op = op.as_ref().map_or("", |op| op.text());
+                self.write(op);
+
+                if op == "not" {
+                    self.write(" ");
+                }
+
+                self.optional_expr(unary.operand());
+            }
+            Expr::BinaryExpr(binary) => {
+                self.optional_expr(binary.lhs());
+                self.write(" ");
+                self.write(binary.op().as_ref().map_or("", |op| op.text()));
+                self.write(" ");
+                self.optional_expr(binary.rhs());
+            }
+            Expr::LogicalExpr(logical) => {
+                self.optional_expr(logical.lhs());
+                self.write(" ");
+                self.write(logical.op().as_ref().map_or("", |op| op.text()));
+                self.write(" ");
+                self.optional_expr(logical.rhs());
+            }
+            Expr::CallExpr(call) => {
+                self.optional_expr(call.callee());
+                let args: Vec<_> = call
+                    .arg_list()
+                    .map(|list| list.args().collect())
+                    .unwrap_or_default();
+                self.args(&args);
+            }
+            Expr::FnExpr(function) => {
+                self.write("fn ");
+                self.function(function.param_list(), function.ret_type(), function.body());
+            }
+            Expr::InterpolationExpr(interpolation) => {
+                for element in interpolation.syntax().children_with_tokens() {
+                    match element {
+                        SyntaxElement::Token(token) if !token.kind().is_trivia() => {
+                            self.write(token.text())
+                        }
+                        SyntaxElement::Token(_) => {}
+                        SyntaxElement::Node(node) => {
+                            if let Some(expr) = Expr::cast(node) {
+                                self.expr(&expr);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Prints call arguments on the current line if they fit, otherwise puts
+    /// each argument on its own line.
+    fn args(&mut self, args: &[Expr]) {
+        self.write("(");
+
+        if !self.wrap || args.is_empty() || self.args_fit(args) {
+            for (i, arg) in args.iter().enumerate() {
+                if i > 0 {
+                    self.write(", ");
+                }
+                self.expr(arg);
+            }
+
+            self.write(")");
+            return;
+        }
+
+        self.indent += 1;
+        for (i, arg) in args.iter().enumerate() {
+            self.newline();
+            self.expr(arg);
+
+            if i + 1 < args.len() {
+                self.write(",");
+            }
+        }
+        self.indent -= 1;
+        self.newline();
+        self.write(")");
+    }
+
+    /// True when call arguments and the closing `)` fit on the rest of the
+    /// current line without wrapping any calls nested in them.
+    fn args_fit(&self, args: &[Expr]) -> bool {
+        let mut line = Printer::new(self.formatter, self.indent, self.column());
+        line.wrap = false;
+        line.args(args);
+
+        let first_line = line.out.lines().next().unwrap_or_default();
+        line.start_column + first_line.chars().count() <= self.formatter.max_width
+    }
+}
+
+/// True when a statement has a comment that is not in a nested block or
+/// among its leading doc comments, so it cannot be reformatted without
+/// moving the comment.
+fn has_inner_comments(node: &SyntaxNode) -> bool {
+    let mut seen_significant = false;
+
+    node.children_with_tokens().any(|element| match element {
+        SyntaxElement::Token(token) => {
+            let kind = token.kind();
+            let leading = !seen_significant;
+            seen_significant |= !kind.is_trivia();
+
+            matches!(
+                kind,
+                SyntaxKind::Comment | SyntaxKind::BlockComment | SyntaxKind::DocComment
+            ) && !(leading && kind == SyntaxKind::DocComment)
+        }
+        SyntaxElement::Node(child) => {
+            seen_significant = true;
+            child.kind() != SyntaxKind::Block && has_inner_comments(&child)
+        }
+    })
+}
@@ -0,0 +1,69 @@
+//! Comparing identifiers and warning about ones that are easy to misread.
+//!
+//! Identifiers may use any Unicode letters (see UAX #31), which means two
+//! names can look the same while being made of different code points. Names
+//! are normalized to NFC so that equivalent spellings refer to the same
+//! variable, and `warnings` points out names that only look alike or that mix
+//! scripts, such as a Cyrillic `а` hidden in an otherwise Latin name.
+
+use std::collections::{HashMap, HashSet};
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::{MixedScript, confusable_detection::skeleton};
+
+use crate::{diagnostics::Diagnostic, scanner::Scanner};
+
+/// Normalizes an identifier to NFC, the form used when comparing names.
+pub fn normalize(name: &str) -> String {
+    name.nfc().collect()
+}
+
+/// Warnings for identifiers in `source` that mix scripts or that could be
+/// confused with another identifier in the same source. Each distinct name is
+/// only warned about once.
+///
+/// Names made only of ASCII are never reported as confusable with each other,
+/// otherwise pairs like `l` and `I` would be flagged in ordinary programs.
+pub fn warnings(source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut seen = HashSet::new();
+    let mut skeletons: HashMap<String, String> = HashMap::new();
+
+    for lexeme in Scanner::new(source) {
+        let Some(name) = lexeme.identifier_name(source) else {
+            continue;
+        };
+
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+
+        if !name.as_str().is_single_script() {
+            diagnostics.push(
+                Diagnostic::warning(
+                    format!("identifier `{name}` mixes characters from different scripts"),
+                    lexeme.span(),
+                )
+                .with_help("write the name using letters from a single script"),
+            );
+        }
+
+        let skeleton: String = skeleton(&name).collect();
+        match skeletons.get(&skeleton) {
+            Some(other) if !(name.is_ascii() && other.is_ascii()) => diagnostics.push(
+                Diagnostic::warning(
+                    format!("identifier `{name}` can be confused with `{other}`"),
+                    lexeme.span(),
+                )
+                .with_label(format!("looks like `{other}`"))
+                .with_help("rename one of them so they can be told apart"),
+            ),
+            Some(_) => {}
+            None => {
+                skeletons.insert(skeleton, name);
+            }
+        }
+    }
+
+    diagnostics
+}
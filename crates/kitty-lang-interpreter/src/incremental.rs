@@ -0,0 +1,133 @@
+//! Updating the lexemes of a source file after an edit by rescanning only the
+//! part of the source the edit could have changed, for editors that lex the
+//! buffer on every keystroke.
+//!
+//! Scanning restarts at a lexeme shortly before the edit and stops as soon as
+//! it produces a lexeme at the same place, and with the same scanner state, as
+//! a lexeme from before the edit. The remaining lexemes are reused with their
+//! positions shifted. Multi-line strings and block comments are single
+//! lexemes, so an edit that opens or closes one keeps rescanning until the
+//! lexemes line up again.
+
+use std::ops::Range;
+
+use crate::scanner::{Lexeme, Scanner, ScannerState};
+
+/// A change to a source file: the bytes in `range` of the old source are
+/// replaced with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Applies the edit to `source`, returning the edited source.
+    pub fn apply(&self, source: &str) -> String {
+        let mut edited = source.to_string();
+        edited.replace_range(self.range.clone(), &self.replacement);
+        edited
+    }
+}
+
+/// Returns the lexemes of `source`, the text after `edit` was applied, given
+/// `lexemes` scanned from the text before the edit. The result is the same as
+/// scanning all of `source` again.
+pub fn relex(source: &str, lexemes: &[Lexeme], edit: &TextEdit) -> Vec<Lexeme> {
+    let edit_end = edit.range.start + edit.replacement.len();
+    let byte_shift = edit_end as isize - edit.range.end as isize;
+
+    // A lexeme ending right at the edit may be extended by it, eg `a` + `b`,
+    // and scanning can look a char or two past the end of a lexeme, so the
+    // lexeme before the first one touching the edit is rescanned as well.
+    let touched = lexemes.partition_point(|l| l.byte_range().end < edit.range.start);
+    let restart = touched.saturating_sub(1);
+
+    let mut relexed = lexemes[..restart].to_vec();
+    let mut state = ScannerState::default();
+    for lexeme in &relexed {
+        state.follow(lexeme, Some(lexeme.text(source)));
+    }
+
+    let (index, byte_index) = match lexemes.get(restart) {
+        Some(lexeme) if restart > 0 => (lexeme.index, lexeme.byte_index),
+        _ => (0, 0),
+    };
+
+    // The state of the old scan at each old lexeme is tracked alongside the
+    // new scan, and becomes unknown if it depends on text that was replaced.
+    let mut old_state = Some(state.clone());
+    let mut old = restart;
+    let mut scanner = Scanner::resume(source, index, byte_index, state);
+
+    loop {
+        let new_state = scanner.state().clone();
+        let Some(lexeme) = scanner.next() else {
+            return relexed;
+        };
+
+        while let Some(old_lexeme) = lexemes.get(old)
+            && shifted_start(old_lexeme, edit, byte_shift) < lexeme.byte_index
+        {
+            if let Some(state) = &mut old_state
+                && !state.follow(old_lexeme, old_text(source, old_lexeme, edit, byte_shift))
+            {
+                old_state = None;
+            }
+            old += 1;
+        }
+
+        if lexeme.byte_index >= edit_end
+            && let Some(old_lexeme) = lexemes.get(old)
+            && old_lexeme.byte_index >= edit.range.end
+            && shifted_start(old_lexeme, edit, byte_shift) == lexeme.byte_index
+            && old_state.as_ref() == Some(&new_state)
+        {
+            let index_shift = lexeme.index as isize - old_lexeme.index as isize;
+            relexed.extend(lexemes[old..].iter().map(|l| Lexeme {
+                index: l.index.strict_add_signed(index_shift),
+                byte_index: l.byte_index.strict_add_signed(byte_shift),
+                ..l.clone()
+            }));
+            return relexed;
+        }
+
+        relexed.push(lexeme);
+    }
+}
+
+/// Where an old lexeme starts in the edited source. Lexemes starting inside
+/// the replaced text have no position there and are placed at its start.
+fn shifted_start(lexeme: &Lexeme, edit: &TextEdit, byte_shift: isize) -> usize {
+    if lexeme.byte_index >= edit.range.end {
+        lexeme.byte_index.strict_add_signed(byte_shift)
+    } else {
+        lexeme.byte_index.min(edit.range.start)
+    }
+}
+
+/// The text of an old lexeme, or `None` if the edit replaced any of it.
+fn old_text<'a>(
+    source: &'a str,
+    lexeme: &Lexeme,
+    edit: &TextEdit,
+    byte_shift: isize,
+) -> Option<&'a str> {
+    let range = lexeme.byte_range();
+
+    if range.end <= edit.range.start {
+        Some(&source[range])
+    } else if range.start >= edit.range.end {
+        let start = range.start.strict_add_signed(byte_shift);
+        Some(&source[start..start + lexeme.byte_length])
+    } else {
+        None
+    }
+}
@@ -0,0 +1,506 @@
+mod environment;
+mod error;
+mod natives;
+mod value;
+
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+use kitty_lang_ast::{
+    expr::{BinaryOp, Expr, ExprKind, Literal, LogicalOp, UnaryOp},
+    span::Span,
+    stmt::{Function, Mutability, Stmt, StmtKind},
+};
+
+use crate::interpreter::environment::{AssignError, Environment};
+
+pub use error::RuntimeError;
+pub use value::{Callable, NativeFn, Value};
+
+/// Maximum number of nested function calls before evaluation is aborted.
+pub const MAX_CALL_DEPTH: usize = 200;
+
+/// How control leaves a statement.
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+/// Tree walking evaluator for kitty programs.
+///
+/// Global declarations persist across calls to `run`, so an interpreter can
+/// execute a program piece by piece.
+pub struct Interpreter {
+    globals: Rc<RefCell<Environment>>,
+    environment: Rc<RefCell<Environment>>,
+    output: Box<dyn Write>,
+    call_depth: usize,
+    /// Command line arguments passed to the script being run.
+    args: Vec<String>,
+}
+
+impl Interpreter {
+    /// Creates an interpreter that writes program output to stdout.
+    pub fn new() -> Self {
+        Self::with_output(Box::new(std::io::stdout()))
+    }
+
+    /// Creates an interpreter that writes program output to `output`.
+    pub fn with_output(output: Box<dyn Write>) -> Self {
+        let globals = Rc::new(RefCell::new(Environment::default()));
+        let mut interpreter = Self {
+            environment: globals.clone(),
+            globals,
+            output,
+            call_depth: 0,
+            args: Vec::new(),
+        };
+
+        natives::define_natives(&mut interpreter);
+        interpreter
+    }
+
+    /// Writer that receives program output.
+    pub fn output(&mut self) -> &mut dyn Write {
+        &mut self.output
+    }
+
+    /// Command line arguments available to the program via `arg_count()` and
+    /// `arg(i)`.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn set_args(&mut self, args: Vec<String>) {
+        self.args = args;
+    }
+
+    /// Defines a global function implemented in Rust.
+    pub fn define_native(&mut self, name: &'static str, arity: Option<usize>, func: NativeFn) {
+        self.globals.borrow_mut().define(
+            name,
+            Value::Function(Rc::new(Callable::Native { name, arity, func })),
+            Mutability::Const,
+        );
+    }
+
+    /// Looks up the current value of a global variable.
+    pub fn global(&self, name: &str) -> Option<Value> {
+        self.globals.borrow().get(name)
+    }
+
+    /// Executes each statement in `program` in the global scope.
+    pub fn run(&mut self, program: &[Stmt]) -> Result<(), RuntimeError> {
+        for stmt in program {
+            self.execute(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates an expression in the global scope.
+    pub fn evaluate(&mut self, expr: &Expr) -> Result<Value, RuntimeError> {
+        self.eval(expr)
+    }
+
+    fn execute(&mut self, stmt: &Stmt) -> Result<Flow, RuntimeError> {
+        match &stmt.kind {
+            StmtKind::Expression(expr) => {
+                self.eval(expr)?;
+            }
+            StmtKind::Var(decl) => {
+                let value = match &decl.initializer {
+                    Some(expr) => self.eval(expr)?,
+                    None => Value::Null,
+                };
+
+                self.environment
+                    .borrow_mut()
+                    .define(&decl.name.name, value, decl.mutability);
+            }
+            StmtKind::Fn(function) => {
+                let name = &function.name.as_ref().expect("declared fn has a name").name;
+                let closure = self.make_closure(function);
+
+                self.environment
+                    .borrow_mut()
+                    .define(name, closure, Mutability::Var);
+            }
+            StmtKind::Block(stmts) => {
+                let environment = Environment::new_enclosed(self.environment.clone());
+                return self.execute_block(stmts, Rc::new(RefCell::new(environment)));
+            }
+            StmtKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if self.eval_bool(condition)? {
+                    return self.execute(then_branch);
+                } else if let Some(else_branch) = else_branch {
+                    return self.execute(else_branch);
+                }
+            }
+            StmtKind::While { condition, body } => {
+                while self.eval_bool(condition)? {
+                    match self.execute(body)? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+                }
+            }
+            StmtKind::For {
+                initializer,
+                condition,
+                increment,
+                body,
+            } => {
+                // Variables declared in the initializer are scoped to the loop.
+                let environment = Environment::new_enclosed(self.environment.clone());
+                let previous =
+                    std::mem::replace(&mut self.environment, Rc::new(RefCell::new(environment)));
+
+                let result = self.execute_for(
+                    initializer.as_deref(),
+                    condition.as_ref(),
+                    increment.as_ref(),
+                    body,
+                );
+
+                self.environment = previous;
+                return result;
+            }
+            StmtKind::Break => return Ok(Flow::Break),
+            StmtKind::Continue => return Ok(Flow::Continue),
+            StmtKind::Return(value) => {
+                let value = match value {
+                    Some(expr) => self.eval(expr)?,
+                    None => Value::Null,
+                };
+
+                return Ok(Flow::Return(value));
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    fn execute_for(
+        &mut self,
+        initializer: Option<&Stmt>,
+        condition: Option<&Expr>,
+        increment: Option<&Expr>,
+        body: &Stmt,
+    ) -> Result<Flow, RuntimeError> {
+        if let Some(initializer) = initializer {
+            self.execute(initializer)?;
+        }
+
+        loop {
+            if let Some(condition) = condition
+                && !self.eval_bool(condition)?
+            {
+                break;
+            }
+
+            match self.execute(body)? {
+                Flow::Break => break,
+                Flow::Return(value) => return Ok(Flow::Return(value)),
+                Flow::Normal | Flow::Continue => {}
+            }
+
+            if let Some(increment) = increment {
+                self.eval(increment)?;
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    /// Executes `stmts` with `environment` as the current scope, restoring the
+    /// previous scope afterwards.
+    fn execute_block(
+        &mut self,
+        stmts: &[Stmt],
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<Flow, RuntimeError> {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let mut result = Ok(Flow::Normal);
+
+        for stmt in stmts {
+            match self.execute(stmt) {
+                Ok(Flow::Normal) => {}
+                other => {
+                    result = other;
+                    break;
+                }
+            }
+        }
+
+        self.environment = previous;
+        result
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value, RuntimeError> {
+        match &expr.kind {
+            ExprKind::Literal(literal) => Ok(match literal {
+                Literal::Int(v) => Value::Int(*v),
+                Literal::Float(v) => Value::Float(*v),
+                Literal::String(v) => Value::String(v.as_str().into()),
+                Literal::Bool(v) => Value::Bool(*v),
+                Literal::Null => Value::Null,
+            }),
+            ExprKind::Variable(name) => {
+                self.environment
+                    .borrow()
+                    .get(name)
+                    .ok_or_else(|| RuntimeError::UndefinedVariable {
+                        name: name.clone(),
+                        span: expr.span,
+                    })
+            }
+            ExprKind::Assign { target, value } => {
+                let value = self.eval(value)?;
+
+                match self
+                    .environment
+                    .borrow_mut()
+                    .assign(&target.name, value.clone())
+                {
+                    Ok(()) => Ok(value),
+                    Err(AssignError::Undefined) => Err(RuntimeError::UndefinedVariable {
+                        name: target.name.clone(),
+                        span: target.span,
+                    }),
+                    Err(AssignError::Constant) => Err(RuntimeError::AssignToConstant {
+                        name: target.name.clone(),
+                        span: target.span,
+                    }),
+                }
+            }
+            ExprKind::Grouping(expr) => self.eval(expr),
+            ExprKind::Call { callee, args } => {
+                let callee = self.eval(callee)?;
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                self.call(callee, &args, expr.span)
+            }
+            ExprKind::Function(function) => Ok(self.make_closure(function)),
+            ExprKind::Interpolation(parts) => {
+                let mut text = String::new();
+                for part in parts {
+                    text.push_str(&self.eval(part)?.to_string());
+                }
+                Ok(Value::String(text.into()))
+            }
+            ExprKind::Unary { op, expr: operand } => {
+                let value = self.eval(operand)?;
+                eval_unary(*op, value, expr.span)
+            }
+            ExprKind::Binary { op, left, right } => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                eval_binary(*op, left, right, expr.span)
+            }
+            ExprKind::Logical { op, left, right } => {
+                let left = self.eval_bool(left)?;
+
+                match (op, left) {
+                    (LogicalOp::And, false) => Ok(Value::Bool(false)),
+                    (LogicalOp::Or, true) => Ok(Value::Bool(true)),
+                    _ => Ok(Value::Bool(self.eval_bool(right)?)),
+                }
+            }
+        }
+    }
+
+    fn eval_bool(&mut self, expr: &Expr) -> Result<bool, RuntimeError> {
+        match self.eval(expr)? {
+            Value::Bool(v) => Ok(v),
+            other => Err(RuntimeError::ExpectedBool {
+                found: other.type_name(),
+                span: expr.span,
+            }),
+        }
+    }
+
+    fn make_closure(&self, function: &Rc<Function>) -> Value {
+        Value::Function(Rc::new(Callable::Closure {
+            function: function.clone(),
+            environment: self.environment.clone(),
+        }))
+    }
+
+    /// Invokes `callee` with `args`. `span` covers the call expression.
+    pub fn call(
+        &mut self,
+        callee: Value,
+        args: &[Value],
+        span: Span,
+    ) -> Result<Value, RuntimeError> {
+        let Value::Function(callable) = callee else {
+            return Err(RuntimeError::NotCallable {
+                found: callee.type_name(),
+                span,
+            });
+        };
+
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(RuntimeError::StackOverflow {
+                limit: MAX_CALL_DEPTH,
+                span,
+            });
+        }
+
+        self.call_depth += 1;
+        let result = self.call_callable(&callable, args, span);
+        self.call_depth -= 1;
+
+        result
+    }
+
+    fn call_callable(
+        &mut self,
+        callable: &Callable,
+        args: &[Value],
+        span: Span,
+    ) -> Result<Value, RuntimeError> {
+        match callable {
+            Callable::Closure {
+                function,
+                environment,
+            } => {
+                check_arity(function.params.len(), args.len(), span)?;
+
+                let mut scope = Environment::new_enclosed(environment.clone());
+                for (param, arg) in function.params.iter().zip(args) {
+                    scope.define(&param.name.name, arg.clone(), Mutability::Var);
+                }
+
+                match self.execute_block(&function.body, Rc::new(RefCell::new(scope)))? {
+                    Flow::Return(value) => Ok(value),
+                    _ => Ok(Value::Null),
+                }
+            }
+            Callable::Native { arity, func, .. } => {
+                if let Some(arity) = arity {
+                    check_arity(*arity, args.len(), span)?;
+                }
+
+                func(self, args, span)
+            }
+        }
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check_arity(expected: usize, found: usize, span: Span) -> Result<(), RuntimeError> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(RuntimeError::ArityMismatch {
+            expected,
+            found,
+            span,
+        })
+    }
+}
+
+fn eval_unary(op: UnaryOp, value: Value, span: Span) -> Result<Value, RuntimeError> {
+    match (op, value) {
+        (UnaryOp::Negate, Value::Int(v)) => v
+            .checked_neg()
+            .map(Value::Int)
+            .ok_or(RuntimeError::IntegerOverflow { span }),
+        (UnaryOp::Negate, Value::Float(v)) => Ok(Value::Float(-v)),
+        (UnaryOp::Not, Value::Bool(v)) => Ok(Value::Bool(!v)),
+        (op, value) => Err(RuntimeError::InvalidUnaryOperand {
+            op: op.as_str(),
+            operand: value.type_name(),
+            span,
+        }),
+    }
+}
+
+fn eval_binary(op: BinaryOp, left: Value, right: Value, span: Span) -> Result<Value, RuntimeError> {
+    use Value::Int;
+
+    let invalid = |left: &Value, right: &Value| RuntimeError::InvalidBinaryOperands {
+        op: op.as_str(),
+        left: left.type_name(),
+        right: right.type_name(),
+        span,
+    };
+
+    match op {
+        BinaryOp::Equal => Ok(Value::Bool(left == right)),
+        BinaryOp::NotEqual => Ok(Value::Bool(left != right)),
+        BinaryOp::Add => match (&left, &right) {
+            (Int(a), Int(b)) => checked(a.checked_add(*b), span),
+            (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{a}{b}").into())),
+            _ => float_op(&left, &right, |a, b| a + b).ok_or_else(|| invalid(&left, &right)),
+        },
+        BinaryOp::Subtract => match (&left, &right) {
+            (Int(a), Int(b)) => checked(a.checked_sub(*b), span),
+            _ => float_op(&left, &right, |a, b| a - b).ok_or_else(|| invalid(&left, &right)),
+        },
+        BinaryOp::Multiply => match (&left, &right) {
+            (Int(a), Int(b)) => checked(a.checked_mul(*b), span),
+            _ => float_op(&left, &right, |a, b| a * b).ok_or_else(|| invalid(&left, &right)),
+        },
+        BinaryOp::Divide => match (&left, &right) {
+            (Int(_), Int(0)) => Err(RuntimeError::DivisionByZero { span }),
+            (Int(a), Int(b)) => checked(a.checked_div(*b), span),
+            _ => float_op(&left, &right, |a, b| a / b).ok_or_else(|| invalid(&left, &right)),
+        },
+        BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => {
+            let ordering = match (&left, &right) {
+                (Int(a), Int(b)) => Some(a.cmp(b)),
+                (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+                _ => match (as_float(&left), as_float(&right)) {
+                    (Some(a), Some(b)) => a.partial_cmp(&b),
+                    _ => return Err(invalid(&left, &right)),
+                },
+            };
+
+            // Comparisons involving NaN are always false.
+            let result = ordering.is_some_and(|ordering| match op {
+                BinaryOp::Less => ordering.is_lt(),
+                BinaryOp::LessEqual => ordering.is_le(),
+                BinaryOp::Greater => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            });
+
+            Ok(Value::Bool(result))
+        }
+    }
+}
+
+fn checked(value: Option<i64>, span: Span) -> Result<Value, RuntimeError> {
+    value
+        .map(Value::Int)
+        .ok_or(RuntimeError::IntegerOverflow { span })
+}
+
+/// Applies `f` to two numbers when at least one of them is a float.
+fn float_op<F: Fn(f64, f64) -> f64>(left: &Value, right: &Value, f: F) -> Option<Value> {
+    Some(Value::Float(f(as_float(left)?, as_float(right)?)))
+}
+
+fn as_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(v) => Some(*v as f64),
+        Value::Float(v) => Some(*v),
+        _ => None,
+    }
+}
@@ -0,0 +1,62 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use kitty_lang_ast::stmt::Mutability;
+
+use crate::interpreter::Value;
+
+/// A lexical scope holding the variables declared in it, and a link to the
+/// scope that encloses it.
+#[derive(Default)]
+pub struct Environment {
+    values: HashMap<String, Binding>,
+    enclosing: Option<Rc<RefCell<Environment>>>,
+}
+
+struct Binding {
+    value: Value,
+    mutability: Mutability,
+}
+
+pub enum AssignError {
+    Undefined,
+    Constant,
+}
+
+impl Environment {
+    pub fn new_enclosed(enclosing: Rc<RefCell<Environment>>) -> Self {
+        Self {
+            values: HashMap::new(),
+            enclosing: Some(enclosing),
+        }
+    }
+
+    /// Declares a variable in this scope, replacing any previous declaration
+    /// with the same name.
+    pub fn define(&mut self, name: &str, value: Value, mutability: Mutability) {
+        self.values
+            .insert(name.to_string(), Binding { value, mutability });
+    }
+
+    /// Looks up a variable in this scope or any enclosing scope.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        match self.values.get(name) {
+            Some(binding) => Some(binding.value.clone()),
+            None => self.enclosing.as_ref()?.borrow().get(name),
+        }
+    }
+
+    /// Updates the value of the variable with the closest enclosing declaration.
+    pub fn assign(&mut self, name: &str, value: Value) -> Result<(), AssignError> {
+        match self.values.get_mut(name) {
+            Some(binding) if binding.mutability == Mutability::Const => Err(AssignError::Constant),
+            Some(binding) => {
+                binding.value = value;
+                Ok(())
+            }
+            None => match &self.enclosing {
+                Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+                None => Err(AssignError::Undefined),
+            },
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use kitty_lang_ast::span::Span;
+use thiserror::Error;
+
+/// An error raised while evaluating a kitty program.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RuntimeError {
+    #[error("undefined variable `{name}`")]
+    UndefinedVariable { name: String, span: Span },
+    #[error("cannot assign to constant `{name}`")]
+    AssignToConstant { name: String, span: Span },
+    #[error("cannot apply `{op}` to {operand}")]
+    InvalidUnaryOperand {
+        op: &'static str,
+        operand: &'static str,
+        span: Span,
+    },
+    #[error("cannot apply `{op}` to {left} and {right}")]
+    InvalidBinaryOperands {
+        op: &'static str,
+        left: &'static str,
+        right: &'static str,
+        span: Span,
+    },
+    #[error("expected a bool but found {found}")]
+    ExpectedBool { found: &'static str, span: Span },
+    #[error("division by zero")]
+    DivisionByZero { span: Span },
+    #[error("integer overflow")]
+    IntegerOverflow { span: Span },
+    #[error("{found} is not callable")]
+    NotCallable { found: &'static str, span: Span },
+    #[error("expected {expected} arguments but got {found}")]
+    ArityMismatch {
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    #[error("maximum call depth of {limit} exceeded")]
+    StackOverflow { limit: usize, span: Span },
+    /// An error reported by a native function.
+    #[error("{message}")]
+    Native { message: String, span: Span },
+}
+
+impl RuntimeError {
+    /// The region of source that caused the error.
+    pub fn span(&self) -> Span {
+        match self {
+            RuntimeError::UndefinedVariable { span, .. }
+            | RuntimeError::AssignToConstant { span, .. }
+            | RuntimeError::InvalidUnaryOperand { span, .. }
+            | RuntimeError::InvalidBinaryOperands { span, .. }
+            | RuntimeError::ExpectedBool { span, .. }
+            | RuntimeError::DivisionByZero { span }
+            | RuntimeError::IntegerOverflow { span }
+            | RuntimeError::NotCallable { span, .. }
+            | RuntimeError::ArityMismatch { span, .. }
+            | RuntimeError::StackOverflow { span, .. }
+            | RuntimeError::Native { span, .. } => *span,
+        }
+    }
+}
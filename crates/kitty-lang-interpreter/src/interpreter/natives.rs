@@ -0,0 +1,52 @@
+use kitty_lang_ast::span::Span;
+
+use crate::interpreter::{Interpreter, RuntimeError, Value};
+
+/// Registers the functions available to every kitty program.
+pub fn define_natives(interpreter: &mut Interpreter) {
+    interpreter.define_native("print", None, print);
+    interpreter.define_native("arg_count", Some(0), arg_count);
+    interpreter.define_native("arg", Some(1), arg);
+}
+
+/// Writes each argument separated by a space, followed by a newline.
+fn print(interpreter: &mut Interpreter, args: &[Value], span: Span) -> Result<Value, RuntimeError> {
+    let line = args
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    writeln!(interpreter.output(), "{line}").map_err(|e| RuntimeError::Native {
+        message: format!("failed to write output: {e}"),
+        span,
+    })?;
+
+    Ok(Value::Null)
+}
+
+/// Returns the number of command line arguments passed to the script.
+fn arg_count(
+    interpreter: &mut Interpreter,
+    _args: &[Value],
+    _span: Span,
+) -> Result<Value, RuntimeError> {
+    Ok(Value::Int(interpreter.args().len() as i64))
+}
+
+/// Returns the command line argument at the given index, or null if there is
+/// no argument at that index.
+fn arg(interpreter: &mut Interpreter, args: &[Value], span: Span) -> Result<Value, RuntimeError> {
+    let Value::Int(index) = args[0] else {
+        return Err(RuntimeError::Native {
+            message: format!("arg expects an int index but got {}", args[0].type_name()),
+            span,
+        });
+    };
+
+    Ok(usize::try_from(index)
+        .ok()
+        .and_then(|i| interpreter.args().get(i))
+        .map(|a| Value::String(a.as_str().into()))
+        .unwrap_or(Value::Null))
+}
@@ -0,0 +1,95 @@
+use std::{cell::RefCell, fmt, rc::Rc};
+
+use kitty_lang_ast::{span::Span, stmt::Function};
+
+use crate::interpreter::{Interpreter, RuntimeError, environment::Environment};
+
+/// A value produced by evaluating kitty code.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    String(Rc<str>),
+    Bool(bool),
+    Null,
+    Function(Rc<Callable>),
+}
+
+impl Value {
+    /// Name of the value's type as it would be written in kitty code.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::String(_) => "string",
+            Value::Bool(_) => "bool",
+            Value::Null => "null",
+            Value::Function(_) => "function",
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b,
+            (Value::Int(a), Value::Float(b)) | (Value::Float(b), Value::Int(a)) => {
+                (*a as f64) == *b
+            }
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(v) => write!(f, "{v}"),
+            // Debug formatting keeps the trailing `.0` on whole numbers so
+            // floats are distinguishable from ints.
+            Value::Float(v) => write!(f, "{v:?}"),
+            Value::String(v) => f.write_str(v),
+            Value::Bool(v) => write!(f, "{v}"),
+            Value::Null => f.write_str("null"),
+            Value::Function(callable) => write!(f, "{callable:?}"),
+        }
+    }
+}
+
+/// Signature of a function implemented in Rust that can be called from kitty
+/// code. `span` covers the call expression.
+pub type NativeFn = fn(&mut Interpreter, &[Value], Span) -> Result<Value, RuntimeError>;
+
+/// Something that can be invoked with a call expression.
+pub enum Callable {
+    /// A kitty function along with the environment it was declared in.
+    Closure {
+        function: Rc<Function>,
+        environment: Rc<RefCell<Environment>>,
+    },
+    Native {
+        name: &'static str,
+        /// Number of arguments required, or `None` if any number is accepted.
+        arity: Option<usize>,
+        func: NativeFn,
+    },
+}
+
+impl fmt::Debug for Callable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Closures can (indirectly) reference themselves through their
+        // environment so only the name is printed.
+        match self {
+            Callable::Closure { function, .. } => match &function.name {
+                Some(name) => write!(f, "<fn {}>", name.name),
+                None => f.write_str("<fn>"),
+            },
+            Callable::Native { name, .. } => write!(f, "<native fn {name}>"),
+        }
+    }
+}
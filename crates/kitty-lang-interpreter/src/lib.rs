@@ -1,2 +1,13 @@
+pub mod cst_parser;
+pub mod diagnostics;
+pub mod formatter;
+pub mod identifiers;
+pub mod incremental;
+pub mod interpreter;
+pub mod literals;
+pub mod parser;
 pub mod scanner;
+pub mod source;
 pub mod tokens;
+pub mod trivia;
+pub mod typeck;
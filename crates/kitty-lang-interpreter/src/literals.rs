@@ -0,0 +1,209 @@
+//! Decoding the values of literal tokens from their source text.
+
+use std::num::IntErrorKind;
+
+use thiserror::Error;
+
+/// An escape sequence in a string literal that is not supported.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid escape sequence `{text}`")]
+pub struct InvalidEscape {
+    /// The escape sequence, including its leading `\`.
+    pub text: String,
+    /// Byte offset of the `\` starting the escape, relative to the start of
+    /// the decoded text.
+    pub byte_index: usize,
+}
+
+/// A number literal that could not be converted to a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum NumberError {
+    #[error("not a valid number literal")]
+    Invalid,
+    #[error("number literal is out of range")]
+    OutOfRange,
+}
+
+/// Parses the text of an int literal such as `42`, `-1_000`, `0xff`, `0o17` or
+/// `0b1010`.
+pub fn parse_int(text: &str) -> Result<i64, NumberError> {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text),
+    };
+
+    let (radix, digits) = match unsigned.get(..2) {
+        Some("0x" | "0X") => (16, &unsigned[2..]),
+        Some("0o" | "0O") => (8, &unsigned[2..]),
+        Some("0b" | "0B") => (2, &unsigned[2..]),
+        _ => (10, unsigned),
+    };
+
+    // `from_str_radix` accepts a leading `+` which kitty does not.
+    if !digits.starts_with(|c: char| c.is_digit(radix)) {
+        return Err(NumberError::Invalid);
+    }
+
+    // Parsing the sign together with the digits allows `i64::MIN`.
+    let digits = format!("{sign}{}", digits.replace('_', ""));
+
+    i64::from_str_radix(&digits, radix).map_err(|e| match e.kind() {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => NumberError::OutOfRange,
+        _ => NumberError::Invalid,
+    })
+}
+
+/// Parses the text of a float literal such as `1.5`, `2.`, `1_000.0` or
+/// `1.5e-3`. Values too large to represent are out of range rather than
+/// infinite.
+pub fn parse_float(text: &str) -> Result<f64, NumberError> {
+    let unsigned = text.strip_prefix('-').unwrap_or(text);
+
+    if !unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(NumberError::Invalid);
+    }
+
+    let value = text
+        .replace('_', "")
+        .parse::<f64>()
+        .map_err(|_| NumberError::Invalid)?;
+
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(NumberError::OutOfRange)
+    }
+}
+
+/// Removes the delimiter lines and common indentation from the contents of a
+/// multi-line string (the text between its `"""`).
+///
+/// A line break directly after the opening `"""` is dropped, as is the
+/// whitespace before the closing `"""` when it is on a line of its own. The
+/// indentation shared by the remaining lines and the closing `"""` is then
+/// removed from every line:
+///
+/// ```text
+/// var s = """
+///     hello
+///       world
+///     """;
+/// ```
+///
+/// gives `"hello\n  world"`. Text on the same line as the opening `"""` is
+/// kept as written. Lines containing only whitespace become empty and `\r\n`
+/// line endings become `\n`.
+pub fn dedent(text: &str) -> String {
+    let text = text.replace("\r\n", "\n");
+    let mut lines: Vec<&str> = text.split('\n').collect();
+
+    // Text after the opening `"""` is not indented relative to anything, so it
+    // is set aside instead of being dedented.
+    let first = lines.remove(0);
+    let first = (lines.is_empty() || !is_blank(first)).then_some(first);
+
+    // The closing line is removed but still counts towards the indentation.
+    let mut indent = None;
+    if let Some(last) = lines.last()
+        && is_blank(last)
+    {
+        indent = Some(*last);
+        lines.pop();
+    }
+
+    for line in lines.iter().filter(|l| !is_blank(l)) {
+        let whitespace = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+        indent = Some(match indent {
+            None => whitespace,
+            Some(indent) => common_prefix(indent, whitespace),
+        });
+    }
+
+    let indent = indent.unwrap_or("");
+    first
+        .into_iter()
+        .chain(lines.iter().map(|line| {
+            if is_blank(line) {
+                ""
+            } else {
+                line.strip_prefix(indent).unwrap_or(line)
+            }
+        }))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_blank(line: &str) -> bool {
+    line.chars().all(|c| c == ' ' || c == '\t')
+}
+
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let length = a.bytes().zip(b.bytes()).take_while(|(a, b)| a == b).count();
+    &a[..length]
+}
+
+/// Replaces the escape sequences in the contents of a string literal (the text
+/// between its quotes) with the characters they represent.
+///
+/// Supported escapes are `\"`, `\\`, `\{`, `\}`, `\n`, `\r`, `\t`, `\0` and
+/// `\u{...}` with one to six hex digits naming a Unicode scalar value.
+pub fn unescape_string(text: &str) -> Result<String, InvalidEscape> {
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+
+        let invalid = |end: usize| InvalidEscape {
+            text: text[start..end].to_string(),
+            byte_index: start,
+        };
+
+        let escaped = match chars.next() {
+            Some((_, '"')) => '"',
+            Some((_, '\\')) => '\\',
+            Some((_, '{')) => '{',
+            Some((_, '}')) => '}',
+            Some((_, 'n')) => '\n',
+            Some((_, 'r')) => '\r',
+            Some((_, 't')) => '\t',
+            Some((_, '0')) => '\0',
+            Some((i, 'u')) => {
+                let mut end = i + 1;
+                if chars.next_if(|(_, c)| *c == '{').is_none() {
+                    return Err(invalid(end));
+                }
+                end += 1;
+
+                let mut digits = String::new();
+                while let Some((i, c)) = chars.next_if(|(_, c)| c.is_ascii_hexdigit()) {
+                    digits.push(c);
+                    end = i + 1;
+                }
+
+                let Some((i, _)) = chars.next_if(|(_, c)| *c == '}') else {
+                    return Err(invalid(end));
+                };
+                end = i + 1;
+
+                if digits.is_empty() || digits.len() > 6 {
+                    return Err(invalid(end));
+                }
+
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| invalid(end))?
+            }
+            Some((i, c)) => return Err(invalid(i + c.len_utf8())),
+            None => return Err(invalid(text.len())),
+        };
+
+        value.push(escaped);
+    }
+
+    Ok(value)
+}
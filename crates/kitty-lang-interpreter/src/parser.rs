@@ -0,0 +1,988 @@
+use std::{collections::HashMap, rc::Rc};
+
+use kitty_lang_ast::{
+    expr::{BinaryOp, Expr, ExprKind, Ident, Literal, LogicalOp, UnaryOp},
+    span::Span,
+    stmt::{Function, Mutability, Param, Stmt, StmtKind, VarDecl},
+    types::{TypeExpr, TypeExprKind},
+};
+use thiserror::Error;
+
+use crate::{
+    literals::{parse_float, parse_int},
+    scanner::{Lexeme, Scanner},
+    tokens::{InvalidTokenReason, Token},
+};
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ParseError {
+    #[error("expected {expected} but found {found}")]
+    UnexpectedToken {
+        expected: &'static str,
+        found: Token,
+        span: Span,
+    },
+    #[error("expected {expected} but found keyword `{keyword}`")]
+    KeywordAsIdentifier {
+        expected: &'static str,
+        keyword: &'static str,
+        span: Span,
+    },
+    #[error("expected {expected} but reached the end of input")]
+    UnexpectedEof { expected: &'static str, span: Span },
+    #[error("{}", reason.describe(text))]
+    InvalidToken {
+        reason: InvalidTokenReason,
+        /// The source text of the invalid token.
+        text: String,
+        span: Span,
+    },
+    #[error("number literal is out of range")]
+    NumberOutOfRange {
+        /// `Int` or `Float`, the kind of literal that is out of range.
+        token: Token,
+        span: Span,
+    },
+    #[error("invalid assignment target")]
+    InvalidAssignmentTarget { span: Span },
+    #[error("`break` can only be used inside a loop")]
+    BreakOutsideLoop { span: Span },
+    #[error("`continue` can only be used inside a loop")]
+    ContinueOutsideLoop { span: Span },
+    #[error("`return` can only be used inside a function")]
+    ReturnOutsideFunction { span: Span },
+}
+
+impl ParseError {
+    /// The region of source that caused the error.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::KeywordAsIdentifier { span, .. }
+            | ParseError::UnexpectedEof { span, .. }
+            | ParseError::InvalidToken { span, .. }
+            | ParseError::NumberOutOfRange { span, .. }
+            | ParseError::InvalidAssignmentTarget { span }
+            | ParseError::BreakOutsideLoop { span }
+            | ParseError::ContinueOutsideLoop { span }
+            | ParseError::ReturnOutsideFunction { span } => *span,
+        }
+    }
+}
+
+/// Recursive descent parser that turns the lexemes produced by `Scanner` into
+/// `kitty_lang_ast` nodes.
+///
+/// Statement grammar:
+///
+/// ```text
+/// program     -> declaration* EOF
+/// declaration -> varDecl | fnDecl | statement
+/// varDecl     -> ( "var" IDENTIFIER typeAnnotation? ( "=" expression )?
+///                | "const" IDENTIFIER typeAnnotation? "=" expression ) ";"
+/// fnDecl      -> "fn" IDENTIFIER function
+/// function    -> "(" ( param ( "," param )* )? ")" ( "->" type )? block
+/// param       -> IDENTIFIER typeAnnotation?
+/// statement   -> block | ifStmt | whileStmt | forStmt | breakStmt
+///              | continueStmt | returnStmt | exprStmt
+/// block       -> "{" declaration* "}"
+/// ifStmt      -> "if" "(" expression ")" statement ( "else" statement )?
+/// whileStmt   -> "while" "(" expression ")" statement
+/// forStmt     -> "for" "(" ( varDecl | exprStmt | ";" ) expression? ";"
+///                expression? ")" statement
+/// returnStmt  -> "return" expression? ";"
+/// exprStmt    -> expression ";"
+/// ```
+///
+/// Expression grammar, from lowest to highest precedence:
+///
+/// ```text
+/// expression -> assignment
+/// assignment -> IDENTIFIER "=" assignment | or
+/// or         -> and ( "or" and )*
+/// and        -> equality ( "and" equality )*
+/// equality   -> comparison ( ( "==" | "!=" ) comparison )*
+/// comparison -> term ( ( "<" | "<=" | ">" | ">=" ) term )*
+/// term       -> factor ( ( "+" | "-" ) factor )*
+/// factor     -> unary ( ( "*" | "/" ) unary )*
+/// unary      -> ( "-" | "not" ) unary | call
+/// call       -> primary ( "(" ( expression ( "," expression )* )? ")" )*
+/// primary    -> INT | FLOAT | STRING | RAW_STRING | MULTILINE_STRING
+///             | "true" | "false" | "null"
+///             | IDENTIFIER | "(" expression ")" | "fn" function
+///             | interpolation
+/// interpolation -> STRING_START expression ( STRING_MIDDLE expression )*
+///                  STRING_END
+/// ```
+///
+/// Type grammar:
+///
+/// ```text
+/// typeAnnotation -> ":" type
+/// type           -> IDENTIFIER | "null"
+///                 | "fn" "(" ( type ( "," type )* )? ")" ( "->" type )?
+/// ```
+pub struct Parser<'a> {
+    source: &'a str,
+    lexemes: Vec<Lexeme>,
+    /// Doc comment text, keyed by the index of the lexeme that follows the
+    /// comment. Doc comments are only kept for the declarations they document
+    /// and are otherwise treated like ordinary comments.
+    docs: HashMap<usize, String>,
+    current: usize,
+    /// Number of loops enclosing the statement currently being parsed.
+    loop_depth: usize,
+    /// Number of functions enclosing the statement currently being parsed.
+    function_depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let mut lexemes = Vec::new();
+        let mut docs = HashMap::new();
+        let mut doc_lines = Vec::new();
+
+        for lexeme in Scanner::new(source) {
+            match lexeme.token {
+                Token::Comment | Token::BlockComment => {}
+                Token::DocComment => doc_lines.extend(lexeme.doc_text(source)),
+                _ => {
+                    if !doc_lines.is_empty() {
+                        docs.insert(lexemes.len(), doc_lines.join("\n"));
+                        doc_lines.clear();
+                    }
+                    lexemes.push(lexeme);
+                }
+            }
+        }
+
+        Self {
+            source,
+            lexemes,
+            docs,
+            current: 0,
+            loop_depth: 0,
+            function_depth: 0,
+        }
+    }
+
+    /// Parses every declaration in the source. When a declaration fails to
+    /// parse the parser skips ahead to the next statement and keeps going, so
+    /// that all errors in the source are reported at once.
+    pub fn parse_program(&mut self) -> Result<Vec<Stmt>, Vec<ParseError>> {
+        let mut stmts = Vec::new();
+        let mut errors = Vec::new();
+
+        while self.peek().is_some() {
+            let start = self.current;
+
+            match self.declaration() {
+                Ok(stmt) => stmts.push(stmt),
+                Err(error) => {
+                    errors.push(error);
+                    self.synchronize();
+
+                    // Always make progress, even if the error was reported on
+                    // a lexeme that looks like the start of a statement.
+                    if self.current == start {
+                        self.advance();
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(stmts)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Parses a single expression that must span the rest of the input.
+    pub fn parse_expression(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.expression()?;
+
+        match self.peek() {
+            Some(lexeme) => Err(self.unexpected(lexeme, "end of input")),
+            None => Ok(expr),
+        }
+    }
+
+    fn declaration(&mut self) -> Result<Stmt, ParseError> {
+        match self.peek().map(|l| l.token) {
+            Some(Token::Var) => self.var_declaration(Mutability::Var),
+            Some(Token::Const) => self.var_declaration(Mutability::Const),
+            Some(Token::Fn) if self.peek_next().map(|l| l.token) == Some(Token::Identifier) => {
+                self.fn_declaration()
+            }
+            _ => self.statement(),
+        }
+    }
+
+    fn fn_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let doc = self.docs.remove(&self.current);
+        let keyword = self.advance().span();
+        let name = self.identifier("a function name")?;
+        let (mut function, end) = self.function(Some(name))?;
+        function.doc = doc;
+
+        Ok(Stmt::new(StmtKind::Fn(Rc::new(function)), keyword.to(end)))
+    }
+
+    /// Parses the parameter list and body of a function, returning the
+    /// function and the span of its closing brace.
+    fn function(&mut self, name: Option<Ident>) -> Result<(Function, Span), ParseError> {
+        self.consume(Token::LeftParen, "'(' before function parameters")?;
+        let mut params = Vec::new();
+
+        if self.try_consume(Token::RightParen).is_none() {
+            loop {
+                params.push(Param {
+                    name: self.identifier("a parameter name")?,
+                    ty: self.type_annotation()?,
+                });
+
+                if self.try_consume(Token::Comma).is_none() {
+                    break;
+                }
+            }
+
+            self.consume(Token::RightParen, "')' after function parameters")?;
+        }
+
+        let return_type = match self.try_consume(Token::Arrow) {
+            Some(_) => Some(self.type_expr()?),
+            None => None,
+        };
+
+        // Loops do not extend into the function body, so `break` inside of a
+        // function nested in a loop is an error.
+        let loop_depth = std::mem::replace(&mut self.loop_depth, 0);
+        self.function_depth += 1;
+
+        let body = self.block();
+
+        self.function_depth -= 1;
+        self.loop_depth = loop_depth;
+
+        let body = body?;
+        let StmtKind::Block(stmts) = body.kind else {
+            unreachable!("block() always returns a block statement");
+        };
+
+        Ok((
+            Function {
+                name,
+                params,
+                return_type,
+                body: stmts,
+                doc: None,
+            },
+            body.span,
+        ))
+    }
+
+    fn var_declaration(&mut self, mutability: Mutability) -> Result<Stmt, ParseError> {
+        let doc = self.docs.remove(&self.current);
+        let keyword = self.advance().span();
+        let name = self.identifier("a variable name")?;
+        let ty = self.type_annotation()?;
+
+        let initializer = match mutability {
+            Mutability::Var => match self.try_consume(Token::Equal) {
+                Some(_) => Some(self.expression()?),
+                None => None,
+            },
+            Mutability::Const => {
+                self.consume(Token::Equal, "'=' after constant name")?;
+                Some(self.expression()?)
+            }
+        };
+
+        let semicolon = self.consume(Token::Semicolon, "';' after variable declaration")?;
+
+        Ok(Stmt::new(
+            StmtKind::Var(VarDecl {
+                name,
+                mutability,
+                ty,
+                initializer,
+                doc,
+            }),
+            keyword.to(semicolon.span()),
+        ))
+    }
+
+    /// Parses an optional `: type` after a variable or parameter name.
+    fn type_annotation(&mut self) -> Result<Option<TypeExpr>, ParseError> {
+        match self.try_consume(Token::Colon) {
+            Some(_) => Ok(Some(self.type_expr()?)),
+            None => Ok(None),
+        }
+    }
+
+    fn type_expr(&mut self) -> Result<TypeExpr, ParseError> {
+        let Some(lexeme) = self.peek().cloned() else {
+            return Err(self.unexpected_eof("a type"));
+        };
+
+        match lexeme.token {
+            Token::Identifier => {
+                self.advance();
+                Ok(TypeExpr::new(
+                    TypeExprKind::Name(self.identifier_name(&lexeme)),
+                    lexeme.span(),
+                ))
+            }
+            Token::Null => {
+                self.advance();
+                Ok(TypeExpr::new(
+                    TypeExprKind::Name("null".to_string()),
+                    lexeme.span(),
+                ))
+            }
+            Token::Fn => {
+                self.advance();
+                self.consume(Token::LeftParen, "'(' after 'fn'")?;
+                let mut params = Vec::new();
+
+                let mut end = match self.try_consume(Token::RightParen) {
+                    Some(close) => close.span(),
+                    None => {
+                        loop {
+                            params.push(self.type_expr()?);
+
+                            if self.try_consume(Token::Comma).is_none() {
+                                break;
+                            }
+                        }
+
+                        self.consume(Token::RightParen, "')' after parameter types")?
+                            .span()
+                    }
+                };
+
+                let ret = match self.try_consume(Token::Arrow) {
+                    Some(_) => {
+                        let ret = self.type_expr()?;
+                        end = ret.span;
+                        Some(Box::new(ret))
+                    }
+                    None => None,
+                };
+
+                Ok(TypeExpr::new(
+                    TypeExprKind::Function { params, ret },
+                    lexeme.span().to(end),
+                ))
+            }
+            _ => Err(self.unexpected(&lexeme, "a type")),
+        }
+    }
+
+    fn statement(&mut self) -> Result<Stmt, ParseError> {
+        match self.peek().map(|l| l.token) {
+            Some(Token::LeftBrace) => self.block(),
+            Some(Token::If) => self.if_statement(),
+            Some(Token::While) => self.while_statement(),
+            Some(Token::For) => self.for_statement(),
+            Some(Token::Break) => self.break_statement(),
+            Some(Token::Continue) => self.continue_statement(),
+            Some(Token::Return) => self.return_statement(),
+            _ => self.expression_statement(),
+        }
+    }
+
+    fn block(&mut self) -> Result<Stmt, ParseError> {
+        let open = self.consume(Token::LeftBrace, "'{'")?.span();
+        let mut stmts = Vec::new();
+
+        loop {
+            match self.peek().map(|l| l.token) {
+                Some(Token::RightBrace) => break,
+                Some(_) => stmts.push(self.declaration()?),
+                None => return Err(self.unexpected_eof("'}' after block")),
+            }
+        }
+
+        let close = self.advance().span();
+        Ok(Stmt::new(StmtKind::Block(stmts), open.to(close)))
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.advance().span();
+        self.consume(Token::LeftParen, "'(' after 'if'")?;
+        let condition = self.expression()?;
+        self.consume(Token::RightParen, "')' after if condition")?;
+
+        let then_branch = self.statement()?;
+        let else_branch = match self.try_consume(Token::Else) {
+            Some(_) => Some(Box::new(self.statement()?)),
+            None => None,
+        };
+
+        let end = else_branch.as_ref().map_or(then_branch.span, |s| s.span);
+
+        Ok(Stmt::new(
+            StmtKind::If {
+                condition,
+                then_branch: Box::new(then_branch),
+                else_branch,
+            },
+            keyword.to(end),
+        ))
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.advance().span();
+        self.consume(Token::LeftParen, "'(' after 'while'")?;
+        let condition = self.expression()?;
+        self.consume(Token::RightParen, "')' after while condition")?;
+
+        let body = self.loop_body()?;
+        let span = keyword.to(body.span);
+
+        Ok(Stmt::new(
+            StmtKind::While {
+                condition,
+                body: Box::new(body),
+            },
+            span,
+        ))
+    }
+
+    fn for_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.advance().span();
+        self.consume(Token::LeftParen, "'(' after 'for'")?;
+
+        let initializer = match self.peek().map(|l| l.token) {
+            Some(Token::Semicolon) => {
+                self.advance();
+                None
+            }
+            Some(Token::Var) => Some(Box::new(self.var_declaration(Mutability::Var)?)),
+            Some(Token::Const) => Some(Box::new(self.var_declaration(Mutability::Const)?)),
+            _ => Some(Box::new(self.expression_statement()?)),
+        };
+
+        let condition = match self.peek().map(|l| l.token) {
+            Some(Token::Semicolon) => None,
+            _ => Some(self.expression()?),
+        };
+        self.consume(Token::Semicolon, "';' after loop condition")?;
+
+        let increment = match self.peek().map(|l| l.token) {
+            Some(Token::RightParen) => None,
+            _ => Some(self.expression()?),
+        };
+        self.consume(Token::RightParen, "')' after for clauses")?;
+
+        let body = self.loop_body()?;
+        let span = keyword.to(body.span);
+
+        Ok(Stmt::new(
+            StmtKind::For {
+                initializer,
+                condition,
+                increment,
+                body: Box::new(body),
+            },
+            span,
+        ))
+    }
+
+    fn loop_body(&mut self) -> Result<Stmt, ParseError> {
+        self.loop_depth += 1;
+        let body = self.statement();
+        self.loop_depth -= 1;
+        body
+    }
+
+    fn break_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.advance().span();
+
+        if self.loop_depth == 0 {
+            return Err(ParseError::BreakOutsideLoop { span: keyword });
+        }
+
+        let semicolon = self.consume(Token::Semicolon, "';' after 'break'")?;
+        Ok(Stmt::new(StmtKind::Break, keyword.to(semicolon.span())))
+    }
+
+    fn continue_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.advance().span();
+
+        if self.loop_depth == 0 {
+            return Err(ParseError::ContinueOutsideLoop { span: keyword });
+        }
+
+        let semicolon = self.consume(Token::Semicolon, "';' after 'continue'")?;
+        Ok(Stmt::new(StmtKind::Continue, keyword.to(semicolon.span())))
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.advance().span();
+
+        if self.function_depth == 0 {
+            return Err(ParseError::ReturnOutsideFunction { span: keyword });
+        }
+
+        let value = match self.peek().map(|l| l.token) {
+            Some(Token::Semicolon) => None,
+            _ => Some(self.expression()?),
+        };
+
+        let semicolon = self.consume(Token::Semicolon, "';' after return value")?;
+        Ok(Stmt::new(
+            StmtKind::Return(value),
+            keyword.to(semicolon.span()),
+        ))
+    }
+
+    fn expression_statement(&mut self) -> Result<Stmt, ParseError> {
+        let expr = self.expression()?;
+        let semicolon = self.consume(Token::Semicolon, "';' after expression")?;
+        let span = expr.span.to(semicolon.span());
+
+        Ok(Stmt::new(StmtKind::Expression(expr), span))
+    }
+
+    fn expression(&mut self) -> Result<Expr, ParseError> {
+        self.assignment()
+    }
+
+    fn assignment(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.or()?;
+
+        if self.try_consume(Token::Equal).is_none() {
+            return Ok(expr);
+        }
+
+        // Assignment is right associative so parse the value by recursing.
+        let value = self.assignment()?;
+
+        match expr.kind {
+            ExprKind::Variable(name) => {
+                let span = expr.span.to(value.span);
+                Ok(Expr::new(
+                    ExprKind::Assign {
+                        target: Ident {
+                            name,
+                            span: expr.span,
+                        },
+                        value: Box::new(value),
+                    },
+                    span,
+                ))
+            }
+            _ => Err(ParseError::InvalidAssignmentTarget { span: expr.span }),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+
+        while self.try_consume(Token::Or).is_some() {
+            let right = self.and()?;
+            expr = make_logical(LogicalOp::Or, expr, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.equality()?;
+
+        while self.try_consume(Token::And).is_some() {
+            let right = self.equality()?;
+            expr = make_logical(LogicalOp::And, expr, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn equality(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.comparison()?;
+
+        while let Some(op) = self.try_consume_binary_op(|t| match t {
+            Token::EqualEqual => Some(BinaryOp::Equal),
+            Token::BangEqual => Some(BinaryOp::NotEqual),
+            _ => None,
+        }) {
+            let right = self.comparison()?;
+            expr = make_binary(op, expr, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.term()?;
+
+        while let Some(op) = self.try_consume_binary_op(|t| match t {
+            Token::Less => Some(BinaryOp::Less),
+            Token::LessEqual => Some(BinaryOp::LessEqual),
+            Token::Greater => Some(BinaryOp::Greater),
+            Token::GreaterEqual => Some(BinaryOp::GreaterEqual),
+            _ => None,
+        }) {
+            let right = self.term()?;
+            expr = make_binary(op, expr, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.factor()?;
+
+        while let Some(op) = self.try_consume_binary_op(|t| match t {
+            Token::Plus => Some(BinaryOp::Add),
+            Token::Minus => Some(BinaryOp::Subtract),
+            _ => None,
+        }) {
+            let right = self.factor()?;
+            expr = make_binary(op, expr, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
+
+        while let Some(op) = self.try_consume_binary_op(|t| match t {
+            Token::Star => Some(BinaryOp::Multiply),
+            Token::Slash => Some(BinaryOp::Divide),
+            _ => None,
+        }) {
+            let right = self.unary()?;
+            expr = make_binary(op, expr, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        let op = match self.peek().map(|l| l.token) {
+            Some(Token::Minus) => UnaryOp::Negate,
+            Some(Token::Not) => UnaryOp::Not,
+            _ => return self.call(),
+        };
+
+        let op_span = self.advance().span();
+
+        // Negative number literals are folded into a single literal, which also
+        // allows `-9223372036854775808` even though the number on its own is
+        // out of range. A literal that is called, eg `-1()`, is left alone.
+        if op == UnaryOp::Negate
+            && let Some(lexeme) = self.peek()
+            && matches!(lexeme.token, Token::Int | Token::Float)
+            && self.peek_next().map(|l| l.token) != Some(Token::LeftParen)
+        {
+            let lexeme = self.advance().clone();
+            let span = op_span.to(lexeme.span());
+            let text = format!("-{}", lexeme.text(self.source));
+            let literal = match lexeme.token {
+                Token::Int => parse_int(&text).map(Literal::Int),
+                _ => parse_float(&text).map(Literal::Float),
+            }
+            .map_err(|_| ParseError::NumberOutOfRange {
+                token: lexeme.token,
+                span,
+            })?;
+
+            return Ok(Expr::new(ExprKind::Literal(literal), span));
+        }
+
+        let expr = self.unary()?;
+        let span = op_span.to(expr.span);
+
+        Ok(Expr::new(
+            ExprKind::Unary {
+                op,
+                expr: Box::new(expr),
+            },
+            span,
+        ))
+    }
+
+    fn call(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.primary()?;
+
+        while self.try_consume(Token::LeftParen).is_some() {
+            let mut args = Vec::new();
+
+            if self.peek().map(|l| l.token) != Some(Token::RightParen) {
+                loop {
+                    args.push(self.expression()?);
+
+                    if self.try_consume(Token::Comma).is_none() {
+                        break;
+                    }
+                }
+            }
+
+            let close = self.consume(Token::RightParen, "')' after arguments")?;
+            let span = expr.span.to(close.span());
+
+            expr = Expr::new(
+                ExprKind::Call {
+                    callee: Box::new(expr),
+                    args,
+                },
+                span,
+            );
+        }
+
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let Some(lexeme) = self.peek() else {
+            return Err(self.unexpected_eof("an expression"));
+        };
+
+        let span = lexeme.span();
+        let kind =
+            match lexeme.token {
+                Token::True => ExprKind::Literal(Literal::Bool(true)),
+                Token::False => ExprKind::Literal(Literal::Bool(false)),
+                Token::Null => ExprKind::Literal(Literal::Null),
+                Token::Int => {
+                    let value = lexeme.int_value(self.source).map_err(|_| {
+                        ParseError::NumberOutOfRange {
+                            token: Token::Int,
+                            span,
+                        }
+                    })?;
+                    ExprKind::Literal(Literal::Int(value))
+                }
+                Token::Float => {
+                    let value = lexeme.float_value(self.source).map_err(|_| {
+                        ParseError::NumberOutOfRange {
+                            token: Token::Float,
+                            span,
+                        }
+                    })?;
+                    ExprKind::Literal(Literal::Float(value))
+                }
+                Token::String | Token::RawString | Token::MultilineString => ExprKind::Literal(
+                    Literal::String(lexeme.string_value(self.source).unwrap_or_default()),
+                ),
+                Token::StringStart => return self.interpolation(),
+                Token::Identifier => ExprKind::Variable(self.identifier_name(lexeme)),
+                Token::LeftParen => {
+                    self.advance();
+                    let expr = self.expression()?;
+                    let close = self.consume(Token::RightParen, "')' after expression")?;
+
+                    return Ok(Expr::new(
+                        ExprKind::Grouping(Box::new(expr)),
+                        span.to(close.span()),
+                    ));
+                }
+                Token::Fn => {
+                    self.advance();
+                    let (function, end) = self.function(None)?;
+
+                    return Ok(Expr::new(
+                        ExprKind::Function(Rc::new(function)),
+                        span.to(end),
+                    ));
+                }
+                _ => return Err(self.unexpected(lexeme, "an expression")),
+            };
+
+        self.advance();
+        Ok(Expr::new(kind, span))
+    }
+
+    fn interpolation(&mut self) -> Result<Expr, ParseError> {
+        let start = self.advance().clone();
+        let mut parts = Vec::new();
+        self.push_string_part(&mut parts, &start);
+
+        loop {
+            parts.push(self.expression()?);
+
+            let expected = "'}' after interpolated expression";
+            let lexeme = match self.peek() {
+                Some(l) if matches!(l.token, Token::StringMiddle | Token::StringEnd) => {
+                    self.advance().clone()
+                }
+                Some(l) => return Err(self.unexpected(l, expected)),
+                None => return Err(self.unexpected_eof(expected)),
+            };
+
+            self.push_string_part(&mut parts, &lexeme);
+
+            if lexeme.token == Token::StringEnd {
+                return Ok(Expr::new(
+                    ExprKind::Interpolation(parts),
+                    start.span().to(lexeme.span()),
+                ));
+            }
+        }
+    }
+
+    /// Adds the text of an interpolated string segment to `parts` unless it is
+    /// empty.
+    fn push_string_part(&self, parts: &mut Vec<Expr>, lexeme: &Lexeme) {
+        let value = lexeme.string_value(self.source).unwrap_or_default();
+
+        if !value.is_empty() {
+            parts.push(Expr::new(
+                ExprKind::Literal(Literal::String(value)),
+                lexeme.span(),
+            ));
+        }
+    }
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Lexeme> {
+        self.lexemes.get(self.current)
+    }
+
+    fn peek_next(&self) -> Option<&Lexeme> {
+        self.lexemes.get(self.current + 1)
+    }
+
+    fn advance(&mut self) -> &Lexeme {
+        let lexeme = &self.lexemes[self.current];
+        self.current += 1;
+        lexeme
+    }
+
+    fn identifier(&mut self, expected: &'static str) -> Result<Ident, ParseError> {
+        // The keyword is consumed so that recovering from the error does not
+        // mistake it for the start of the next statement.
+        if let Some(keyword) = self.peek().and_then(|l| l.token.keyword()) {
+            let span = self.advance().span();
+            return Err(ParseError::KeywordAsIdentifier {
+                expected,
+                keyword,
+                span,
+            });
+        }
+
+        let lexeme = self.consume(Token::Identifier, expected)?;
+
+        Ok(Ident {
+            name: self.identifier_name(&lexeme),
+            span: lexeme.span(),
+        })
+    }
+
+    fn try_consume(&mut self, token: Token) -> Option<Lexeme> {
+        if self.peek().map(|l| l.token == token).unwrap_or(false) {
+            Some(self.advance().clone())
+        } else {
+            None
+        }
+    }
+
+    fn try_consume_binary_op<F: Fn(Token) -> Option<BinaryOp>>(
+        &mut self,
+        f: F,
+    ) -> Option<BinaryOp> {
+        let op = self.peek().and_then(|l| f(l.token))?;
+        self.advance();
+        Some(op)
+    }
+
+    fn consume(&mut self, token: Token, expected: &'static str) -> Result<Lexeme, ParseError> {
+        match self.peek() {
+            Some(lexeme) if lexeme.token == token => Ok(self.advance().clone()),
+            Some(lexeme) => Err(self.unexpected(lexeme, expected)),
+            None => Err(self.unexpected_eof(expected)),
+        }
+    }
+
+    /// Discards lexemes until the start of what is likely the next statement
+    /// so that parsing can resume after an error.
+    fn synchronize(&mut self) {
+        self.loop_depth = 0;
+
+        while let Some(lexeme) = self.peek() {
+            match lexeme.token {
+                Token::Semicolon => {
+                    self.advance();
+                    return;
+                }
+                Token::Var
+                | Token::Const
+                | Token::Fn
+                | Token::If
+                | Token::While
+                | Token::For
+                | Token::Break
+                | Token::Continue
+                | Token::Return => return,
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    fn unexpected(&self, lexeme: &Lexeme, expected: &'static str) -> ParseError {
+        match lexeme.token {
+            Token::Invalid(reason) => ParseError::InvalidToken {
+                reason,
+                text: self.text(lexeme),
+                span: lexeme.span(),
+            },
+            found => ParseError::UnexpectedToken {
+                expected,
+                found,
+                span: lexeme.span(),
+            },
+        }
+    }
+
+    fn unexpected_eof(&self, expected: &'static str) -> ParseError {
+        ParseError::UnexpectedEof {
+            expected,
+            span: Span::new(self.source.chars().count(), 0),
+        }
+    }
+
+    /// Get the name of an identifier lexeme, normalized so that identifiers
+    /// written with different but equivalent code points are the same name.
+    fn identifier_name(&self, lexeme: &Lexeme) -> String {
+        lexeme
+            .identifier_name(self.source)
+            .unwrap_or_else(|| self.text(lexeme))
+    }
+
+    /// Get the source text for a lexeme.
+    fn text(&self, lexeme: &Lexeme) -> String {
+        lexeme.text(self.source).to_string()
+    }
+}
+
+fn make_binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+    let span = left.span.to(right.span);
+    Expr::new(
+        ExprKind::Binary {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        },
+        span,
+    )
+}
+
+fn make_logical(op: LogicalOp, left: Expr, right: Expr) -> Expr {
+    let span = left.span.to(right.span);
+    Expr::new(
+        ExprKind::Logical {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        },
+        span,
+    )
+}
@@ -1,7 +1,15 @@
-use crate::tokens::{InvalidTokenReason, Token};
+use std::{iter::Peekable, ops::Range, str::CharIndices};
+
+use kitty_lang_ast::span::Span;
+
+use crate::{
+    identifiers,
+    literals::{NumberError, dedent, parse_float, parse_int, unescape_string},
+    tokens::{InvalidTokenReason, Token},
+};
 
 /// Represents a tokenized sequence of characters generated by the scanner.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Lexeme {
     /// Token representing the lexeme.
     pub token: Token,
@@ -9,43 +17,236 @@ pub struct Lexeme {
     pub index: usize,
     /// Number of characters in the lexeme.
     pub length: usize,
+    /// Offset of the first byte for the lexeme in the source str.
+    pub byte_index: usize,
+    /// Number of bytes in the lexeme.
+    pub byte_length: usize,
+}
+
+impl Lexeme {
+    /// Characters covered by the lexeme.
+    pub fn span(&self) -> Span {
+        Span::new(self.index, self.length)
+    }
+
+    /// Range of bytes covered by the lexeme, suitable for slicing the source
+    /// str directly.
+    pub fn byte_range(&self) -> Range<usize> {
+        self.byte_index..self.byte_index + self.byte_length
+    }
+
+    /// The source text of the lexeme. `source` must be the str the lexeme was
+    /// scanned from.
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.byte_range()]
+    }
+
+    /// The text of a `Token::DocComment` lexeme without its leading `///` and
+    /// the space after it, or `None` for any other token.
+    pub fn doc_text<'a>(&self, source: &'a str) -> Option<&'a str> {
+        if self.token != Token::DocComment {
+            return None;
+        }
+
+        let text = self.text(source).trim_end_matches('\r');
+        let text = &text[3..];
+        Some(text.strip_prefix(' ').unwrap_or(text))
+    }
+
+    /// The name of a `Token::Identifier` lexeme normalized to NFC, or `None`
+    /// for any other token.
+    pub fn identifier_name(&self, source: &str) -> Option<String> {
+        match self.token {
+            Token::Identifier => Some(identifiers::normalize(self.text(source))),
+            _ => None,
+        }
+    }
+
+    /// The value of a `Token::Int` lexeme.
+    pub fn int_value(&self, source: &str) -> Result<i64, NumberError> {
+        match self.token {
+            Token::Int => parse_int(self.text(source)),
+            _ => Err(NumberError::Invalid),
+        }
+    }
+
+    /// The value of a `Token::Float` lexeme.
+    pub fn float_value(&self, source: &str) -> Result<f64, NumberError> {
+        match self.token {
+            Token::Float => parse_float(self.text(source)),
+            _ => Err(NumberError::Invalid),
+        }
+    }
+
+    /// The value of a string lexeme with its delimiters removed and escape
+    /// sequences decoded, or `None` if the lexeme is not a string or part of an
+    /// interpolated string. Raw strings are returned as written, and multi-line
+    /// strings have their indentation removed with `literals::dedent`.
+    pub fn string_value(&self, source: &str) -> Option<String> {
+        let text = self.text(source);
+
+        match self.token {
+            Token::String | Token::StringStart | Token::StringMiddle | Token::StringEnd => {
+                unescape_string(&text[1..text.len() - 1]).ok()
+            }
+            Token::RawString => {
+                let hashes = text[1..].bytes().take_while(|b| *b == b'#').count();
+                Some(text[hashes + 2..text.len() - hashes - 1].to_string())
+            }
+            Token::MultilineString => unescape_string(&dedent(&text[3..text.len() - 3])).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// The part of the scanner's state that carries over from one lexeme to the
+/// next. Everything else is local to the lexeme being scanned, so a scanner
+/// resumed at the start of a lexeme with the state it had there produces the
+/// same lexemes as before.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScannerState {
+    /// Number of unclosed `{` in each interpolated expression being scanned,
+    /// innermost last. A `}` that closes the interpolation resumes scanning
+    /// the enclosing string.
+    interpolations: Vec<usize>,
+}
+
+impl ScannerState {
+    /// Updates the state to what it was after the scanner produced `lexeme`,
+    /// without scanning it again. `text` is the source text of the lexeme,
+    /// which is only needed for invalid strings: they may end an interpolated
+    /// expression or start one depending on their text. Returns false if the
+    /// text was needed but not given, leaving the state unchanged.
+    pub fn follow(&mut self, lexeme: &Lexeme, text: Option<&str>) -> bool {
+        let (continues, starts) = match (lexeme.token, text) {
+            (Token::StringStart, _) => (false, true),
+            (Token::StringMiddle, _) => (true, true),
+            (Token::StringEnd, _) => (true, false),
+            (Token::Invalid(InvalidTokenReason::UnterminatedString), Some(text)) => {
+                (text.starts_with('}'), false)
+            }
+            (Token::Invalid(InvalidTokenReason::InvalidEscape), Some(text)) => {
+                (text.starts_with('}'), text.ends_with('{'))
+            }
+            (
+                Token::Invalid(
+                    InvalidTokenReason::UnterminatedString | InvalidTokenReason::InvalidEscape,
+                ),
+                None,
+            ) => return false,
+            _ => (false, false),
+        };
+
+        match lexeme.token {
+            Token::LeftBrace => {
+                if let Some(depth) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+            }
+            Token::RightBrace => {
+                if let Some(depth) = self.interpolations.last_mut() {
+                    *depth -= 1;
+                }
+            }
+            _ => {}
+        }
+
+        if continues {
+            self.interpolations.pop();
+        }
+        if starts {
+            self.interpolations.push(0);
+        }
+
+        true
+    }
 }
 
 pub struct Scanner<'a> {
-    _source: &'a str,
-    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    /// Byte offset in `source` of the str `chars` iterates over, which is not
+    /// zero for a resumed scanner.
+    offset: usize,
     start_i: usize,
     current_i: usize,
+    start_byte: usize,
+    current_byte: usize,
+    state: ScannerState,
+    /// Emit whitespace and line breaks as lexemes instead of skipping them.
+    trivia: bool,
 }
 
 impl<'a> Scanner<'a> {
     pub fn new(source: &'a str) -> Self {
+        Self::resume(source, 0, 0, ScannerState::default())
+    }
+
+    /// Creates a scanner that starts part way through `source`, at the char
+    /// `index` which begins at byte `byte_index`, with the state the scanner
+    /// had there. Lexeme positions are relative to the start of `source`.
+    pub fn resume(source: &'a str, index: usize, byte_index: usize, state: ScannerState) -> Self {
         Self {
-            _source: source,
-            chars: source.chars().peekable(),
-            start_i: 0,
-            current_i: 0,
+            source,
+            chars: source[byte_index..].char_indices().peekable(),
+            offset: byte_index,
+            start_i: index,
+            current_i: index,
+            start_byte: byte_index,
+            current_byte: byte_index,
+            state,
+            trivia: false,
         }
     }
 
+    /// Makes the scanner emit the whitespace between lexemes as
+    /// `Token::Whitespace` and `Token::Newline` lexemes rather than skipping
+    /// it, so that the text of the lexemes adds up to the whole source. See
+    /// `trivia::LosslessLexemes` for attaching it to the other lexemes.
+    pub fn with_trivia(mut self) -> Self {
+        self.trivia = true;
+        self
+    }
+
+    /// The state carried over to the next lexeme.
+    pub fn state(&self) -> &ScannerState {
+        &self.state
+    }
+
+    /// Pairs each lexeme with the source text it covers, borrowed from the
+    /// source being scanned.
+    pub fn with_text(self) -> impl Iterator<Item = (Lexeme, &'a str)> {
+        let source = self.source;
+        self.map(move |lexeme| {
+            let text = lexeme.text(source);
+            (lexeme, text)
+        })
+    }
+
     fn make_lexeme(&self, token: Token) -> Lexeme {
         Lexeme {
             token,
             index: self.start_i,
-            length: self.current_i - self.start_i + 1,
+            length: self.current_i - self.start_i,
+            byte_index: self.start_byte,
+            byte_length: self.current_byte - self.start_byte,
         }
     }
 
+    /// Makes an invalid lexeme for bad input such as unknown characters or a
+    /// malformed number. The rest of the bad input, up to the next whitespace
+    /// or punctuation, is consumed into the lexeme so that each mistake is
+    /// reported once instead of as a string of smaller errors.
+    ///
+    /// Problems with strings, comments and operators use `make_lexeme` as
+    /// their extent is already known.
     fn make_invalid_lexeme(&mut self, reason: InvalidTokenReason) -> Lexeme {
-        // TODO: consume until a valid token is found.
-        // VALID_TOKENS = [' ', '\n', '+', ...]
+        self.consume_while(|c| !is_delim_char(c) && !is_punctuation_char(c));
         self.make_lexeme(Token::Invalid(reason))
     }
 
     fn try_consume_char(&mut self, expected: char) -> bool {
-        if let Some(c) = self.chars.peek()
-            && *c == expected
-        {
+        if self.peek() == Some(expected) {
             self.advance();
             return true;
         }
@@ -54,8 +255,8 @@ impl<'a> Scanner<'a> {
     }
 
     fn consume_while<F: Fn(char) -> bool>(&mut self, f: F) {
-        while let Some(c) = self.chars.peek() {
-            if !f(*c) {
+        while let Some(c) = self.peek() {
+            if !f(c) {
                 break;
             }
             self.advance();
@@ -64,9 +265,26 @@ impl<'a> Scanner<'a> {
 
     // Advance the scanner forward one character while continuing to read the
     // current lexeme.
-    fn advance(&mut self) {
+    fn advance(&mut self) -> Option<char> {
+        let (i, c) = self.chars.next()?;
         self.current_i += 1;
-        assert!(self.chars.next().is_some());
+        self.current_byte = self.offset + i + c.len_utf8();
+        Some(c)
+    }
+
+    /// The next char of the source without consuming it.
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    /// Source text of the lexeme being scanned.
+    fn text(&self) -> &'a str {
+        &self.source[self.start_byte..self.current_byte]
+    }
+
+    /// Source text after the lexeme being scanned.
+    fn rest(&self) -> &'a str {
+        &self.source[self.current_byte..]
     }
 }
 
@@ -75,30 +293,61 @@ impl Iterator for Scanner<'_> {
 
     fn next(&mut self) -> Option<Self::Item> {
         // Skip whitespace.
-        self.consume_while(is_delim_char);
+        if !self.trivia {
+            self.consume_while(is_delim_char);
+        }
 
         // Try to read the first character of the next lexeme.
         self.start_i = self.current_i;
+        self.start_byte = self.current_byte;
 
-        if let Some(c) = self.chars.next() {
-            let lexeme = match c {
+        if let Some(c) = self.advance() {
+            match c {
+                '\n' => Some(self.make_lexeme(Token::Newline)),
+                '\r' if self.try_consume_char('\n') => Some(self.make_lexeme(Token::Newline)),
+                ' ' | '\t' | '\r' => {
+                    while matches!(self.peek(), Some(' ' | '\t'))
+                        || (self.peek() == Some('\r') && !self.rest().starts_with("\r\n"))
+                    {
+                        self.advance();
+                    }
+                    Some(self.make_lexeme(Token::Whitespace))
+                }
                 '(' => Some(self.make_lexeme(Token::LeftParen)),
                 ')' => Some(self.make_lexeme(Token::RightParen)),
-                '{' => Some(self.make_lexeme(Token::LeftBrace)),
-                '}' => Some(self.make_lexeme(Token::RightBrace)),
+                '{' => {
+                    if let Some(depth) = self.state.interpolations.last_mut() {
+                        *depth += 1;
+                    }
+                    Some(self.make_lexeme(Token::LeftBrace))
+                }
+                '}' => match self.state.interpolations.last_mut() {
+                    Some(0) => {
+                        self.state.interpolations.pop();
+                        Some(self.scan_rest_of_string(true))
+                    }
+                    Some(depth) => {
+                        *depth -= 1;
+                        Some(self.make_lexeme(Token::RightBrace))
+                    }
+                    None => Some(self.make_lexeme(Token::RightBrace)),
+                },
                 '[' => Some(self.make_lexeme(Token::LeftBracket)),
                 ']' => Some(self.make_lexeme(Token::RightBracket)),
                 ';' => Some(self.make_lexeme(Token::Semicolon)),
                 ',' => Some(self.make_lexeme(Token::Comma)),
-                '.' => Some(self.make_lexeme(Token::Period)),
+                ':' => Some(self.make_lexeme(Token::Colon)),
+                '.' => {
+                    if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        self.consume_while(|c| c.is_ascii_digit() || c == '_');
+                        Some(self.make_invalid_lexeme(InvalidTokenReason::LeadingDot))
+                    } else {
+                        Some(self.make_lexeme(Token::Period))
+                    }
+                }
                 '-' => {
-                    if self
-                        .chars
-                        .peek()
-                        .map(|c| c.is_ascii_digit())
-                        .unwrap_or(false)
-                    {
-                        Some(self.scan_rest_of_number())
+                    if self.try_consume_char('>') {
+                        Some(self.make_lexeme(Token::Arrow))
                     } else {
                         Some(self.make_lexeme(Token::Minus))
                     }
@@ -107,9 +356,19 @@ impl Iterator for Scanner<'_> {
                 '*' => Some(self.make_lexeme(Token::Star)),
                 '/' => {
                     if self.try_consume_char('/') {
+                        // `///` starts a doc comment, but four or more slashes
+                        // are an ordinary comment.
+                        let token = if self.try_consume_char('/') && self.peek() != Some('/') {
+                            Token::DocComment
+                        } else {
+                            Token::Comment
+                        };
+
                         // Consume the rest of the line for the comment.
                         self.consume_while(|c| c != '\n');
-                        Some(self.make_lexeme(Token::Comment))
+                        Some(self.make_lexeme(token))
+                    } else if self.try_consume_char('*') {
+                        Some(self.scan_rest_of_block_comment())
                     } else {
                         Some(self.make_lexeme(Token::Slash))
                     }
@@ -139,18 +398,23 @@ impl Iterator for Scanner<'_> {
                     if self.try_consume_char('=') {
                         Some(self.make_lexeme(Token::BangEqual))
                     } else {
-                        Some(self.make_invalid_lexeme(InvalidTokenReason::BangNotSupported))
+                        Some(self.make_lexeme(Token::Invalid(InvalidTokenReason::BangNotSupported)))
+                    }
+                }
+                '"' => {
+                    if self.rest().starts_with("\"\"") {
+                        Some(self.scan_rest_of_multiline_string())
+                    } else {
+                        Some(self.scan_rest_of_string(false))
                     }
                 }
-                '"' => Some(self.scan_rest_of_string()),
                 c if c.is_ascii_digit() => Some(self.scan_rest_of_number()),
-                c if is_ident_lead_char(c) => Some(self.scan_start_of_identifier(c)),
+                'r' if matches!(self.peek(), Some('"' | '#')) => {
+                    Some(self.scan_rest_of_raw_string())
+                }
+                c if is_ident_lead_char(c) => Some(self.scan_rest_of_identifier()),
                 _ => Some(self.make_invalid_lexeme(InvalidTokenReason::UnknownChars)),
-            };
-
-            self.current_i += 1;
-
-            lexeme
+            }
         } else {
             None
         }
@@ -158,121 +422,282 @@ impl Iterator for Scanner<'_> {
 }
 
 impl Scanner<'_> {
-    fn scan_rest_of_string(&mut self) -> Lexeme {
-        self.consume_while(|c| c != '"');
+    /// Scans a string up to its closing quote, or up to the `{` starting an
+    /// interpolated expression. `after_interpolation` is set when continuing a
+    /// string after the `}` closing an interpolated expression.
+    ///
+    /// These strings cannot contain line breaks, so a missing closing quote is
+    /// reported at the end of the line rather than swallowing the rest of the
+    /// file.
+    fn scan_rest_of_string(&mut self, after_interpolation: bool) -> Lexeme {
+        let token = loop {
+            if matches!(self.peek(), Some('\n' | '\r')) {
+                return self.make_lexeme(Token::Invalid(InvalidTokenReason::UnterminatedString));
+            }
+
+            match self.advance() {
+                None => {
+                    return self
+                        .make_lexeme(Token::Invalid(InvalidTokenReason::UnterminatedString));
+                }
+                Some('"') if after_interpolation => break Token::StringEnd,
+                Some('"') => break Token::String,
+                Some('{') => {
+                    self.state.interpolations.push(0);
+                    break if after_interpolation {
+                        Token::StringMiddle
+                    } else {
+                        Token::StringStart
+                    };
+                }
+                // Skip the escaped char so `\"` does not end the string, and
+                // the braces of `\u{...}` so they do not start an interpolated
+                // expression. Escapes are checked once the whole string is read.
+                Some('\\') => {
+                    if self.try_consume_char('u') {
+                        if self.try_consume_char('{') {
+                            self.consume_while(|c| c.is_ascii_hexdigit());
+                            self.try_consume_char('}');
+                        }
+                    } else if !matches!(self.peek(), Some('\n' | '\r')) {
+                        self.advance();
+                    }
+                }
+                Some(_) => {}
+            }
+        };
 
-        if self.chars.peek().is_none() {
-            self.make_invalid_lexeme(InvalidTokenReason::UnterminatedString)
+        // Every string lexeme has a one char delimiter at each end.
+        let text = self.text();
+        let contents = &text[1..text.len() - 1];
+
+        if unescape_string(contents).is_ok() {
+            self.make_lexeme(token)
         } else {
-            // Eat the closing quote.
-            self.advance();
-            self.make_lexeme(Token::String)
+            self.make_lexeme(Token::Invalid(InvalidTokenReason::InvalidEscape))
         }
     }
 
-    fn scan_rest_of_number(&mut self) -> Lexeme {
-        self.consume_while(|c| c.is_ascii_digit());
+    /// Scans a block comment up to the `*/` matching its opening `/*`, which
+    /// has already been consumed.
+    fn scan_rest_of_block_comment(&mut self) -> Lexeme {
+        let mut depth = 1;
 
-        if self.chars.peek() == Some(&'.') {
-            self.advance();
-            self.consume_while(|c| c.is_ascii_digit());
+        while depth > 0 {
+            match self.advance() {
+                None => {
+                    return self
+                        .make_lexeme(Token::Invalid(InvalidTokenReason::UnterminatedComment));
+                }
+                Some('/') if self.try_consume_char('*') => depth += 1,
+                Some('*') if self.try_consume_char('/') => depth -= 1,
+                Some(_) => {}
+            }
+        }
 
-            // Numbers must have a delimiter following the last character.
-            if self.chars.peek().map(|c| is_delim_char(*c)).unwrap_or(true) {
-                self.make_lexeme(Token::Float)
-            } else {
-                self.advance();
-                self.make_invalid_lexeme(InvalidTokenReason::UnknownNumberChars)
+        self.make_lexeme(Token::BlockComment)
+    }
+
+    /// Scans a `"""` string, which can span multiple lines. The opening `"`
+    /// has already been consumed.
+    fn scan_rest_of_multiline_string(&mut self) -> Lexeme {
+        self.advance();
+        self.advance();
+
+        loop {
+            match self.advance() {
+                None => {
+                    return self
+                        .make_lexeme(Token::Invalid(InvalidTokenReason::UnterminatedString));
+                }
+                Some('"') if self.rest().starts_with("\"\"") => {
+                    self.advance();
+                    self.advance();
+                    break;
+                }
+                // Skip the escaped char so `\"""` does not end the string.
+                Some('\\') => {
+                    self.advance();
+                }
+                Some(_) => {}
             }
+        }
+
+        let text = self.text();
+        let contents = &text[3..text.len() - 3];
+
+        if unescape_string(contents).is_ok() {
+            self.make_lexeme(Token::MultilineString)
         } else {
-            // Numbers must have a delimiter following the last character.
-            if self.chars.peek().map(|c| is_delim_char(*c)).unwrap_or(true) {
-                self.make_lexeme(Token::Int)
-            } else {
-                self.advance();
-                self.make_invalid_lexeme(InvalidTokenReason::UnknownNumberChars)
-            }
+            self.make_lexeme(Token::Invalid(InvalidTokenReason::InvalidEscape))
         }
     }
 
-    fn scan_start_of_identifier(&mut self, first_char: char) -> Lexeme {
-        match first_char {
-            'a' => self.scan_maybe_keyword("and", 1, Token::And),
-            'b' => self.scan_maybe_keyword("break", 1, Token::Break),
-            'c' => match self.chars.peek() {
-                Some(&'o') => {
-                    self.advance();
+    /// Scans a raw string such as `r"C:\path"` or `r#"say "hi""#`, where the
+    /// contents are taken literally and the string ends at the first `"`
+    /// followed by as many `#` as the opening quote. The leading `r` has already
+    /// been consumed.
+    fn scan_rest_of_raw_string(&mut self) -> Lexeme {
+        let mut hashes = 0;
+        while self.try_consume_char('#') {
+            hashes += 1;
+        }
 
-                    match self.chars.peek() {
-                        Some(&'n') => {
-                            self.advance();
-
-                            match self.chars.peek() {
-                                Some(&'s') => self.scan_maybe_keyword("const", 3, Token::Const),
-                                Some(&'t') => {
-                                    self.scan_maybe_keyword("continue", 3, Token::Continue)
-                                }
-                                _ => self.make_lexeme(Token::Identifier),
-                            }
-                        }
-                        _ => self.make_lexeme(Token::Identifier),
+        if !self.try_consume_char('"') {
+            return self.make_invalid_lexeme(InvalidTokenReason::UnknownChars);
+        }
+
+        loop {
+            match self.advance() {
+                None => {
+                    return self
+                        .make_lexeme(Token::Invalid(InvalidTokenReason::UnterminatedString));
+                }
+                Some('"') if self.rest().bytes().take_while(|b| *b == b'#').count() >= hashes => {
+                    for _ in 0..hashes {
+                        self.advance();
                     }
+                    return self.make_lexeme(Token::RawString);
                 }
-                _ => self.make_lexeme(Token::Identifier),
-            },
-            'e' => self.scan_maybe_keyword("else", 1, Token::Else),
-            'f' => match self.chars.peek() {
-                Some('a') => self.scan_maybe_keyword("false", 1, Token::False),
-                Some('o') => self.scan_maybe_keyword("for", 1, Token::For),
-                Some('n') => self.scan_maybe_keyword("fn", 1, Token::Fn),
-                _ => self.make_lexeme(Token::Identifier),
-            },
-            'i' => self.scan_maybe_keyword("if", 1, Token::If),
-            'n' => match self.chars.peek() {
-                Some('o') => self.scan_maybe_keyword("not", 1, Token::Not),
-                Some('u') => self.scan_maybe_keyword("null", 1, Token::Null),
-                _ => self.make_lexeme(Token::Identifier),
-            },
-            'o' => self.scan_maybe_keyword("or", 1, Token::Or),
-            't' => self.scan_maybe_keyword("true", 1, Token::True),
-            'r' => self.scan_maybe_keyword("return", 1, Token::Return),
-            'v' => self.scan_maybe_keyword("var", 1, Token::Var),
-            'w' => self.scan_maybe_keyword("while", 1, Token::While),
-            _ => {
-                self.consume_while(|c| c.is_ascii_alphanumeric() || c == '_');
-                self.make_lexeme(Token::Identifier)
+                Some(_) => {}
             }
         }
     }
 
-    fn scan_maybe_keyword(&mut self, keyword_name: &str, skip: usize, token: Token) -> Lexeme {
-        // Scan forward and make sure the next set of chars match `keyword_name`.
-        // If it does not match then the lexeme is an identifier.
-        for keyword_c in keyword_name.chars().skip(skip) {
-            if self.chars.peek().map(|c| *c != keyword_c).unwrap_or(true) {
-                return self.scan_rest_of_identifier();
-            }
+    /// Scans the rest of a number after its first digit has been consumed.
+    /// Numbers never include a sign, `-1` is scanned as `Minus` and `Int`.
+    fn scan_rest_of_number(&mut self) -> Lexeme {
+        let radix = match self.peek() {
+            Some('x' | 'X') => 16,
+            Some('o' | 'O') => 8,
+            Some('b' | 'B') => 2,
+            _ => 10,
+        };
 
+        if radix != 10 && self.text() == "0" {
             self.advance();
+            return self.scan_rest_of_radix_int(radix);
         }
 
-        // This is not a keyword if there are additional identifier chars after the keyword match
-        // finishes succesfully.
-        if self
-            .chars
-            .peek()
-            .map(|c| is_ident_tail_char(*c))
-            .unwrap_or(false)
-        {
-            self.scan_rest_of_identifier()
+        let mut digits = self.scan_digits(10, true);
+        let mut token = Token::Int;
+
+        if self.try_consume_char('.') {
+            digits = digits.and(self.scan_digits(10, false));
+            token = Token::Float;
+        }
+
+        if self.try_consume_char('e') || self.try_consume_char('E') {
+            if !self.try_consume_char('+') {
+                self.try_consume_char('-');
+            }
+
+            let exponent = self.scan_digits(10, false);
+            if exponent.count == 0 && !self.at_ident_tail_char() {
+                return self.make_invalid_lexeme(InvalidTokenReason::MissingExponentDigits);
+            }
+
+            digits = digits.and(exponent);
+            token = Token::Float;
+        }
+
+        // Numbers cannot run directly into identifier characters.
+        if self.at_ident_tail_char() {
+            self.make_invalid_lexeme(InvalidTokenReason::UnknownNumberChars)
+        } else if !digits.separators_valid {
+            self.make_invalid_lexeme(InvalidTokenReason::InvalidDigitSeparator)
         } else {
             self.make_lexeme(token)
         }
     }
 
+    /// Scans the digits of a `0x`, `0o` or `0b` int after its prefix.
+    fn scan_rest_of_radix_int(&mut self, radix: u32) -> Lexeme {
+        let digits = self.scan_digits(radix, false);
+
+        if let Some(c) = self.peek()
+            && is_ident_tail_char(c)
+        {
+            self.advance();
+
+            if c.is_ascii_hexdigit() {
+                self.make_invalid_lexeme(InvalidTokenReason::InvalidDigit)
+            } else {
+                self.make_invalid_lexeme(InvalidTokenReason::UnknownNumberChars)
+            }
+        } else if digits.count == 0 {
+            self.make_invalid_lexeme(InvalidTokenReason::MissingDigits)
+        } else if !digits.separators_valid {
+            self.make_invalid_lexeme(InvalidTokenReason::InvalidDigitSeparator)
+        } else {
+            self.make_lexeme(Token::Int)
+        }
+    }
+
+    /// Consumes a run of digits in the given radix along with any `_` digit
+    /// separators. Separators are only valid between two digits, so
+    /// `after_digit` says if the char before the run was a digit.
+    fn scan_digits(&mut self, radix: u32, after_digit: bool) -> Digits {
+        let mut count = 0;
+        let mut separators_valid = true;
+        let mut previous_is_digit = after_digit;
+        let mut ends_with_separator = false;
+
+        while let Some(c) = self.peek() {
+            if c == '_' {
+                separators_valid &= previous_is_digit;
+                previous_is_digit = false;
+                ends_with_separator = true;
+            } else if c.is_digit(radix) {
+                count += 1;
+                previous_is_digit = true;
+                ends_with_separator = false;
+            } else {
+                break;
+            }
+
+            self.advance();
+        }
+
+        // The run cannot end with a separator.
+        if ends_with_separator {
+            separators_valid = false;
+        }
+
+        Digits {
+            count,
+            separators_valid,
+        }
+    }
+
+    fn at_ident_tail_char(&mut self) -> bool {
+        self.peek().is_some_and(is_ident_tail_char)
+    }
+
+    /// Scans the rest of an identifier, which is a keyword if its name is in
+    /// `KEYWORDS`.
     fn scan_rest_of_identifier(&mut self) -> Lexeme {
         self.consume_while(is_ident_tail_char);
-        self.make_lexeme(Token::Identifier)
+        let token = Token::from_keyword(self.text()).unwrap_or(Token::Identifier);
+        self.make_lexeme(token)
+    }
+}
+
+/// A run of digits read by `Scanner::scan_digits`.
+#[derive(Debug, Clone, Copy)]
+struct Digits {
+    count: usize,
+    /// False if a `_` separator was not between two digits.
+    separators_valid: bool,
+}
+
+impl Digits {
+    /// Combines two runs of digits that make up one number.
+    fn and(self, other: Digits) -> Digits {
+        Digits {
+            count: self.count + other.count,
+            separators_valid: self.separators_valid && other.separators_valid,
+        }
     }
 }
 
@@ -280,10 +705,37 @@ fn is_delim_char(c: char) -> bool {
     c == ' ' || c == '\t' || c == '\r' || c == '\n'
 }
 
+/// Chars that start a token and so end a run of bad input.
+fn is_punctuation_char(c: char) -> bool {
+    matches!(
+        c,
+        '(' | ')'
+            | '{'
+            | '}'
+            | '['
+            | ']'
+            | ';'
+            | ':'
+            | ','
+            | '.'
+            | '+'
+            | '-'
+            | '*'
+            | '/'
+            | '='
+            | '<'
+            | '>'
+            | '!'
+            | '"'
+    )
+}
+
+/// Identifiers follow UAX #31: they start with an XID_Start char or `_`, and
+/// continue with XID_Continue chars.
 fn is_ident_lead_char(c: char) -> bool {
-    c.is_ascii_alphabetic() || c == '_'
+    c == '_' || unicode_ident::is_xid_start(c)
 }
 
 fn is_ident_tail_char(c: char) -> bool {
-    c.is_ascii_alphanumeric() || c == '_'
+    unicode_ident::is_xid_continue(c)
 }
@@ -0,0 +1,148 @@
+use kitty_lang_ast::span::Span;
+
+/// A one based line and column position in a source file. Columns count
+/// characters, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// The text of a source file along with a table of where each line starts,
+/// used to convert between character indices, byte offsets and line/column
+/// locations.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    name: String,
+    text: String,
+    lines: Vec<LineStart>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LineStart {
+    /// Character index of the first character in the line.
+    index: usize,
+    /// Byte offset of the first character in the line.
+    byte_index: usize,
+}
+
+impl SourceFile {
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        let text = text.into();
+        let mut lines = vec![LineStart {
+            index: 0,
+            byte_index: 0,
+        }];
+
+        for (index, (byte_index, c)) in text.char_indices().enumerate() {
+            if c == '\n' {
+                lines.push(LineStart {
+                    index: index + 1,
+                    byte_index: byte_index + 1,
+                });
+            }
+        }
+
+        Self {
+            name: name.into(),
+            text,
+            lines,
+        }
+    }
+
+    /// Name of the file, used when reporting errors.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Text of the given one based line without its line ending, or `None` if
+    /// the line does not exist.
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        let start = self.lines.get(line.checked_sub(1)?)?.byte_index;
+        let end = self
+            .lines
+            .get(line)
+            .map_or(self.text.len(), |next| next.byte_index);
+
+        let text = &self.text[start..end];
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    /// Converts a character index into a line and column. Indices past the end
+    /// of the text are clamped to the end.
+    pub fn location(&self, index: usize) -> Location {
+        let line = self.lines.partition_point(|l| l.index <= index) - 1;
+        let line_start = self.lines[line];
+        let line_length = self.line_text(line + 1).map_or(0, |t| t.chars().count());
+
+        // Allow one past the last character so the end of a line (or file)
+        // can be referenced.
+        Location {
+            line: line + 1,
+            column: (index - line_start.index).min(line_length) + 1,
+        }
+    }
+
+    /// Converts a byte offset into a line and column. Offsets in the middle of
+    /// a multi-byte character refer to that character.
+    pub fn location_of_byte(&self, byte_index: usize) -> Location {
+        let line = self.lines.partition_point(|l| l.byte_index <= byte_index) - 1;
+        let line_start = self.lines[line];
+        let line_text = self.line_text(line + 1).unwrap_or("");
+        let column = line_text
+            .char_indices()
+            .take_while(|(i, c)| line_start.byte_index + i + c.len_utf8() <= byte_index)
+            .count();
+
+        Location {
+            line: line + 1,
+            column: column + 1,
+        }
+    }
+
+    /// Converts a line and column back into a character index, or `None` if
+    /// the location is not in the file. The column one past the end of a line
+    /// is valid and refers to the line ending.
+    pub fn index(&self, location: Location) -> Option<usize> {
+        let line_start = self.lines.get(location.line.checked_sub(1)?)?;
+        let column = location.column.checked_sub(1)?;
+
+        if column > self.line_text(location.line)?.chars().count() {
+            return None;
+        }
+
+        Some(line_start.index + column)
+    }
+
+    /// Converts a character index into a byte offset. Indices past the end of
+    /// the text are clamped to the end.
+    pub fn byte_index(&self, index: usize) -> usize {
+        let line = self.lines.partition_point(|l| l.index <= index) - 1;
+        let line_start = self.lines[line];
+
+        self.text[line_start.byte_index..]
+            .char_indices()
+            .nth(index - line_start.index)
+            .map_or(self.text.len(), |(i, _)| line_start.byte_index + i)
+    }
+
+    /// Source text covered by a span.
+    pub fn slice(&self, span: Span) -> &str {
+        &self.text[self.byte_index(span.index)..self.byte_index(span.end())]
+    }
+}
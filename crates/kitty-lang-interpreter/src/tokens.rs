@@ -1,4 +1,8 @@
-#[derive(Debug, PartialEq)]
+use std::fmt;
+
+use kitty_lang_ast::cst::SyntaxKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Token {
     // Single character tokens.
     LeftParen,
@@ -7,6 +11,7 @@ pub enum Token {
     RightBrace,
     LeftBracket,
     RightBracket,
+    Colon,
     Comma,
     Period,
     Minus,
@@ -20,6 +25,8 @@ pub enum Token {
     Bang,
 
     // Two character tokens.
+    /// The `->` before a function's return type.
+    Arrow,
     BangEqual,
     EqualEqual,
     GreaterEqual,
@@ -28,6 +35,16 @@ pub enum Token {
     // Literals.
     Identifier,
     String,
+    /// Start of an interpolated string up to the first `{`, eg `"hi {`.
+    StringStart,
+    /// Text between two interpolated expressions, eg `} and {`.
+    StringMiddle,
+    /// End of an interpolated string after the last `}`, eg `}!"`.
+    StringEnd,
+    /// A string without escapes or interpolation, eg `r"\d+"` or `r#"a"b"#`.
+    RawString,
+    /// A `"""` string that can span multiple lines.
+    MultilineString,
     Float,
     Int,
 
@@ -51,13 +68,260 @@ pub enum Token {
 
     // Misc.
     Comment,
+    /// A `/* ... */` comment, which may contain nested block comments.
+    BlockComment,
+    /// A `///` comment documenting the declaration that follows it.
+    DocComment,
+    /// Spaces, tabs and lone carriage returns. Only scanned in trivia mode,
+    /// see `Scanner::with_trivia`.
+    Whitespace,
+    /// A `\n` or `\r\n` line break. Only scanned in trivia mode.
+    Newline,
     Invalid(InvalidTokenReason),
 }
 
-#[derive(Debug, PartialEq)]
+/// Every keyword and the token it is scanned as, sorted by name so that it can
+/// be binary searched. This is the one list of keywords: the scanner, the
+/// parser and tools such as syntax highlighters all look keywords up here.
+pub const KEYWORDS: &[(&str, Token)] = &[
+    ("and", Token::And),
+    ("break", Token::Break),
+    ("const", Token::Const),
+    ("continue", Token::Continue),
+    ("else", Token::Else),
+    ("false", Token::False),
+    ("fn", Token::Fn),
+    ("for", Token::For),
+    ("if", Token::If),
+    ("not", Token::Not),
+    ("null", Token::Null),
+    ("or", Token::Or),
+    ("return", Token::Return),
+    ("true", Token::True),
+    ("var", Token::Var),
+    ("while", Token::While),
+];
+
+impl Token {
+    /// The keyword token spelled `name`, or `None` if `name` is not a keyword.
+    pub fn from_keyword(name: &str) -> Option<Token> {
+        KEYWORDS
+            .binary_search_by_key(&name, |(keyword, _)| keyword)
+            .ok()
+            .map(|i| KEYWORDS[i].1)
+    }
+
+    /// The spelling of a keyword token, or `None` for any other token.
+    pub fn keyword(&self) -> Option<&'static str> {
+        KEYWORDS
+            .iter()
+            .find(|(_, token)| token == self)
+            .map(|(keyword, _)| *keyword)
+    }
+
+    pub fn is_keyword(&self) -> bool {
+        self.keyword().is_some()
+    }
+
+    /// The text of a keyword or punctuation token, which is always spelled
+    /// the same way, or `None` for any other token.
+    pub fn spelling(&self) -> Option<&'static str> {
+        let spelling = match self {
+            Token::LeftParen => "(",
+            Token::RightParen => ")",
+            Token::LeftBrace => "{",
+            Token::RightBrace => "}",
+            Token::LeftBracket => "[",
+            Token::RightBracket => "]",
+            Token::Colon => ":",
+            Token::Comma => ",",
+            Token::Period => ".",
+            Token::Minus => "-",
+            Token::Plus => "+",
+            Token::Semicolon => ";",
+            Token::Slash => "/",
+            Token::Star => "*",
+            Token::Equal => "=",
+            Token::Greater => ">",
+            Token::Less => "<",
+            Token::Bang => "!",
+            Token::Arrow => "->",
+            Token::BangEqual => "!=",
+            Token::EqualEqual => "==",
+            Token::GreaterEqual => ">=",
+            Token::LessEqual => "<=",
+            token => return token.keyword(),
+        };
+
+        Some(spelling)
+    }
+
+    /// The kind of syntax tree token the token becomes.
+    pub fn syntax_kind(&self) -> SyntaxKind {
+        match self {
+            Token::LeftParen => SyntaxKind::LeftParen,
+            Token::RightParen => SyntaxKind::RightParen,
+            Token::LeftBrace => SyntaxKind::LeftBrace,
+            Token::RightBrace => SyntaxKind::RightBrace,
+            Token::LeftBracket => SyntaxKind::LeftBracket,
+            Token::RightBracket => SyntaxKind::RightBracket,
+            Token::Colon => SyntaxKind::Colon,
+            Token::Comma => SyntaxKind::Comma,
+            Token::Period => SyntaxKind::Period,
+            Token::Minus => SyntaxKind::Minus,
+            Token::Plus => SyntaxKind::Plus,
+            Token::Semicolon => SyntaxKind::Semicolon,
+            Token::Slash => SyntaxKind::Slash,
+            Token::Star => SyntaxKind::Star,
+            Token::Equal => SyntaxKind::Equal,
+            Token::Greater => SyntaxKind::Greater,
+            Token::Less => SyntaxKind::Less,
+            Token::Bang => SyntaxKind::Bang,
+            Token::Arrow => SyntaxKind::Arrow,
+            Token::BangEqual => SyntaxKind::BangEqual,
+            Token::EqualEqual => SyntaxKind::EqualEqual,
+            Token::GreaterEqual => SyntaxKind::GreaterEqual,
+            Token::LessEqual => SyntaxKind::LessEqual,
+            Token::Identifier => SyntaxKind::Identifier,
+            Token::String => SyntaxKind::String,
+            Token::StringStart => SyntaxKind::StringStart,
+            Token::StringMiddle => SyntaxKind::StringMiddle,
+            Token::StringEnd => SyntaxKind::StringEnd,
+            Token::RawString => SyntaxKind::RawString,
+            Token::MultilineString => SyntaxKind::MultilineString,
+            Token::Float => SyntaxKind::Float,
+            Token::Int => SyntaxKind::Int,
+            Token::And => SyntaxKind::And,
+            Token::Or => SyntaxKind::Or,
+            Token::Not => SyntaxKind::Not,
+            Token::Break => SyntaxKind::Break,
+            Token::Continue => SyntaxKind::Continue,
+            Token::If => SyntaxKind::If,
+            Token::Else => SyntaxKind::Else,
+            Token::True => SyntaxKind::True,
+            Token::False => SyntaxKind::False,
+            Token::Null => SyntaxKind::Null,
+            Token::Fn => SyntaxKind::Fn,
+            Token::For => SyntaxKind::For,
+            Token::Var => SyntaxKind::Var,
+            Token::Const => SyntaxKind::Const,
+            Token::Return => SyntaxKind::Return,
+            Token::While => SyntaxKind::While,
+            Token::Comment => SyntaxKind::Comment,
+            Token::BlockComment => SyntaxKind::BlockComment,
+            Token::DocComment => SyntaxKind::DocComment,
+            Token::Whitespace => SyntaxKind::Whitespace,
+            Token::Newline => SyntaxKind::Newline,
+            Token::Invalid(_) => SyntaxKind::ErrorToken,
+        }
+    }
+
+    /// True for `Whitespace` and `Newline`, the tokens only scanned in trivia
+    /// mode.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Token::Whitespace | Token::Newline)
+    }
+}
+
+/// Names the token by its spelling in backticks, such as `` `{` `` or
+/// `` `while` ``, or in plain words for tokens that have no fixed spelling,
+/// such as "identifier".
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(spelling) = self.spelling() {
+            return write!(f, "`{spelling}`");
+        }
+
+        f.write_str(match self {
+            Token::Identifier => "identifier",
+            Token::String | Token::RawString | Token::MultilineString => "string",
+            Token::StringStart => "start of string",
+            Token::StringMiddle => "middle of string",
+            Token::StringEnd => "end of string",
+            Token::Float => "float",
+            Token::Int => "int",
+            Token::Comment | Token::BlockComment => "comment",
+            Token::DocComment => "doc comment",
+            Token::Whitespace => "whitespace",
+            Token::Newline => "line break",
+            Token::Invalid(reason) => reason.message(),
+            _ => unreachable!("every other token has a spelling"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InvalidTokenReason {
     UnknownChars,
     UnterminatedString,
+    UnterminatedComment,
+    InvalidEscape,
     BangNotSupported,
     UnknownNumberChars,
+    /// A digit that is not valid in the base of a `0x`, `0o` or `0b` number.
+    InvalidDigit,
+    /// A `0x`, `0o` or `0b` prefix that is not followed by any digits.
+    MissingDigits,
+    /// An `e` exponent marker that is not followed by any digits.
+    MissingExponentDigits,
+    /// A `_` digit separator that is not between two digits.
+    InvalidDigitSeparator,
+    /// A float written without a digit before the `.`, eg `.5`.
+    LeadingDot,
+}
+
+impl InvalidTokenReason {
+    /// Describes the problem with the token.
+    pub fn message(&self) -> &'static str {
+        match self {
+            InvalidTokenReason::UnknownChars => "unknown characters",
+            InvalidTokenReason::UnterminatedString => "unterminated string",
+            InvalidTokenReason::UnterminatedComment => "unterminated block comment",
+            InvalidTokenReason::InvalidEscape => "invalid escape sequence in string",
+            InvalidTokenReason::BangNotSupported => "`!` is not an operator",
+            InvalidTokenReason::UnknownNumberChars => "invalid characters in number",
+            InvalidTokenReason::InvalidDigit => "invalid digit for the base of the number",
+            InvalidTokenReason::MissingDigits => "number has no digits after its base prefix",
+            InvalidTokenReason::MissingExponentDigits => "exponent has no digits",
+            InvalidTokenReason::InvalidDigitSeparator => "`_` must be between two digits",
+            InvalidTokenReason::LeadingDot => "float has no digits before its `.`",
+        }
+    }
+
+    /// Describes the problem with a token scanned from `text`. Reasons that
+    /// cover a run of bad input quote it, eg "unknown characters `~~`".
+    pub fn describe(&self, text: &str) -> String {
+        match self {
+            InvalidTokenReason::UnterminatedString
+            | InvalidTokenReason::UnterminatedComment
+            | InvalidTokenReason::InvalidEscape
+            | InvalidTokenReason::BangNotSupported => self.message().to_string(),
+            _ => format!("{} `{text}`", self.message()),
+        }
+    }
+
+    /// Suggests how to fix the token, if there is an obvious fix.
+    pub fn help(&self) -> Option<&'static str> {
+        match self {
+            InvalidTokenReason::UnknownChars => None,
+            InvalidTokenReason::UnterminatedString => Some("add a closing `\"` to end the string"),
+            InvalidTokenReason::UnterminatedComment => {
+                Some("block comments nest, so every `/*` needs its own `*/`")
+            }
+            InvalidTokenReason::InvalidEscape => Some(
+                r#"supported escapes are `\"`, `\\`, `\{`, `\}`, `\n`, `\r`, `\t`, `\0` and `\u{...}`"#,
+            ),
+            InvalidTokenReason::BangNotSupported => Some("use `not` for logical negation"),
+            InvalidTokenReason::UnknownNumberChars => Some("identifiers cannot start with a digit"),
+            InvalidTokenReason::InvalidDigit => {
+                Some("`0b` numbers use digits 0-1, `0o` numbers 0-7 and `0x` numbers 0-9 and a-f")
+            }
+            InvalidTokenReason::MissingDigits => None,
+            InvalidTokenReason::MissingExponentDigits => Some("add digits after the `e`, eg `1e3`"),
+            InvalidTokenReason::InvalidDigitSeparator => {
+                Some("remove the `_` or move it between digits, eg `1_000`")
+            }
+            InvalidTokenReason::LeadingDot => Some("add a zero before the `.`, eg `0.5`"),
+        }
+    }
 }
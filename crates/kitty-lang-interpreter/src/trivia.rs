@@ -0,0 +1,101 @@
+//! Attaching the whitespace between lexemes to the lexemes themselves, so that
+//! tools such as formatters can work with tokens and still reproduce the
+//! source byte for byte.
+//!
+//! Trivia after a lexeme up to the end of its line is the lexeme's trailing
+//! trivia. Everything else, starting with the line break, is leading trivia
+//! of the next lexeme:
+//!
+//! ```text
+//! var x = 1;  // one
+//!     print(x);
+//! ```
+//!
+//! The two spaces after `;` trail it, while the line break and the four
+//! spaces before `print` lead it. Comments are lexemes in their own right
+//! rather than trivia.
+
+use crate::{
+    scanner::{Lexeme, Scanner},
+    tokens::Token,
+};
+
+/// A lexeme with the whitespace around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexemeWithTrivia {
+    /// `Token::Whitespace` and `Token::Newline` lexemes before the lexeme.
+    pub leading: Vec<Lexeme>,
+    pub lexeme: Lexeme,
+    /// `Token::Whitespace` lexemes after the lexeme on the same line.
+    pub trailing: Vec<Lexeme>,
+}
+
+impl LexemeWithTrivia {
+    /// The leading trivia, the lexeme and its trailing trivia in source order.
+    pub fn iter(&self) -> impl Iterator<Item = &Lexeme> {
+        self.leading
+            .iter()
+            .chain(std::iter::once(&self.lexeme))
+            .chain(&self.trailing)
+    }
+}
+
+/// All of the lexemes scanned from a source along with its trivia. The text of
+/// every lexeme and piece of trivia, in order, is exactly the source.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LosslessLexemes {
+    pub lexemes: Vec<LexemeWithTrivia>,
+    /// Trivia after the last lexeme that does not trail it, which is all of
+    /// the trivia when the source has no other lexemes.
+    pub end: Vec<Lexeme>,
+}
+
+impl LosslessLexemes {
+    pub fn scan(source: &str) -> Self {
+        Self::from_lexemes(Scanner::new(source).with_trivia())
+    }
+
+    /// Groups lexemes from a scanner in trivia mode, see
+    /// `Scanner::with_trivia`.
+    pub fn from_lexemes(lexemes: impl IntoIterator<Item = Lexeme>) -> Self {
+        let mut grouped = LosslessLexemes::default();
+        let mut trivia = Vec::new();
+
+        for lexeme in lexemes {
+            if lexeme.token.is_trivia() {
+                trivia.push(lexeme);
+                continue;
+            }
+
+            grouped.attach_trailing(&mut trivia);
+            grouped.lexemes.push(LexemeWithTrivia {
+                leading: std::mem::take(&mut trivia),
+                lexeme,
+                trailing: Vec::new(),
+            });
+        }
+
+        grouped.attach_trailing(&mut trivia);
+        grouped.end = trivia;
+        grouped
+    }
+
+    /// Moves trivia up to the first line break to the last lexeme.
+    fn attach_trailing(&mut self, trivia: &mut Vec<Lexeme>) {
+        if let Some(previous) = self.lexemes.last_mut() {
+            let trailing = trivia
+                .iter()
+                .take_while(|t| t.token != Token::Newline)
+                .count();
+            previous.trailing = trivia.drain(..trailing).collect();
+        }
+    }
+
+    /// Every lexeme and piece of trivia in source order.
+    pub fn iter(&self) -> impl Iterator<Item = &Lexeme> {
+        self.lexemes
+            .iter()
+            .flat_map(LexemeWithTrivia::iter)
+            .chain(&self.end)
+    }
+}
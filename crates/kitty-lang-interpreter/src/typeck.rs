@@ -0,0 +1,615 @@
+//! Static type checking of parsed programs before they run.
+//!
+//! Checking is gradual: anything without a type annotation has type `any`,
+//! which is compatible with every type, so programs without annotations are
+//! checked only for operations that would fail whatever the values are, such
+//! as `1 + "cat"`. Constants take the type of their initializer since they
+//! can never hold anything else. Named functions have a function type from
+//! their parameters, so calls to them are checked against their arity even
+//! when their parameters have no annotations.
+
+use std::{collections::HashMap, fmt};
+
+use kitty_lang_ast::{
+    expr::{BinaryOp, Expr, ExprKind, Literal, UnaryOp},
+    span::Span,
+    stmt::{Function, Mutability, Stmt, StmtKind},
+    types::{TypeExpr, TypeExprKind},
+};
+use thiserror::Error;
+
+/// The type of a value as far as the checker knows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    /// Any value at all, the type of everything without an annotation.
+    Any,
+    Int,
+    Float,
+    String,
+    Bool,
+    Null,
+    Function {
+        /// Parameter types, or `None` for functions such as `print` that take
+        /// any number of arguments.
+        params: Option<Vec<Type>>,
+        ret: Box<Type>,
+    },
+}
+
+impl Type {
+    /// Checks if a value of type `other` can be used where a value of this
+    /// type is expected. Ints can be used as floats, as they can be in
+    /// arithmetic with floats.
+    pub fn accepts(&self, other: &Type) -> bool {
+        match (self, other) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Float, Type::Int) => true,
+            (
+                Type::Function { params, ret },
+                Type::Function {
+                    params: other_params,
+                    ret: other_ret,
+                },
+            ) => {
+                let params_match = match (params, other_params) {
+                    (Some(params), Some(other_params)) => {
+                        params.len() == other_params.len()
+                            && params.iter().zip(other_params).all(|(p, o)| o.accepts(p))
+                    }
+                    _ => true,
+                };
+
+                params_match && ret.accepts(other_ret)
+            }
+            _ => self == other,
+        }
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(self, Type::Int | Type::Float)
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => f.write_str("any"),
+            Type::Int => f.write_str("int"),
+            Type::Float => f.write_str("float"),
+            Type::String => f.write_str("string"),
+            Type::Bool => f.write_str("bool"),
+            Type::Null => f.write_str("null"),
+            Type::Function { params, ret } => {
+                match params {
+                    Some(params) => {
+                        f.write_str("fn(")?;
+                        for (i, param) in params.iter().enumerate() {
+                            if i > 0 {
+                                f.write_str(", ")?;
+                            }
+                            write!(f, "{param}")?;
+                        }
+                        f.write_str(")")?;
+                    }
+                    None => f.write_str("fn(...)")?,
+                }
+
+                write!(f, " -> {ret}")
+            }
+        }
+    }
+}
+
+/// A type error found before a program runs.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TypeError {
+    #[error("expected {expected} but found {found}")]
+    Mismatch {
+        expected: Type,
+        found: Type,
+        span: Span,
+    },
+    #[error("unknown type `{name}`")]
+    UnknownType { name: String, span: Span },
+    #[error("cannot apply `{op}` to {operand}")]
+    InvalidUnaryOperand {
+        op: &'static str,
+        operand: Type,
+        span: Span,
+    },
+    #[error("cannot apply `{op}` to {left} and {right}")]
+    InvalidBinaryOperands {
+        op: &'static str,
+        left: Type,
+        right: Type,
+        span: Span,
+    },
+    #[error("{found} is not callable")]
+    NotCallable { found: Type, span: Span },
+    #[error("expected {expected} arguments but got {found}")]
+    ArityMismatch {
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+}
+
+impl TypeError {
+    /// The region of source that caused the error.
+    pub fn span(&self) -> Span {
+        match self {
+            TypeError::Mismatch { span, .. }
+            | TypeError::UnknownType { span, .. }
+            | TypeError::InvalidUnaryOperand { span, .. }
+            | TypeError::InvalidBinaryOperands { span, .. }
+            | TypeError::NotCallable { span, .. }
+            | TypeError::ArityMismatch { span, .. } => *span,
+        }
+    }
+}
+
+/// Names of the types that can be written in annotations, besides function
+/// types.
+pub const TYPE_NAMES: &[&str] = &["any", "bool", "float", "int", "null", "string"];
+
+/// Checks the types in a program.
+///
+/// Global declarations are remembered across calls to `check`, so a checker
+/// can follow a program that is run piece by piece as the REPL does.
+pub struct TypeChecker {
+    /// Types of the variables in each scope, innermost last.
+    scopes: Vec<HashMap<String, Type>>,
+    /// Return types of the functions enclosing the statement being checked.
+    return_types: Vec<Type>,
+    errors: Vec<TypeError>,
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        let globals = HashMap::from([
+            (
+                "print".to_string(),
+                Type::Function {
+                    params: None,
+                    ret: Box::new(Type::Null),
+                },
+            ),
+            (
+                "arg_count".to_string(),
+                Type::Function {
+                    params: Some(vec![]),
+                    ret: Box::new(Type::Int),
+                },
+            ),
+            (
+                "arg".to_string(),
+                Type::Function {
+                    params: Some(vec![Type::Int]),
+                    ret: Box::new(Type::Any),
+                },
+            ),
+        ]);
+
+        Self {
+            scopes: vec![globals],
+            return_types: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Checks each statement in `program`, returning every type error found.
+    ///
+    /// The globals `program` declares are only remembered if it has no
+    /// errors, since a program with errors is never run.
+    pub fn check(&mut self, program: &[Stmt]) -> Result<(), Vec<TypeError>> {
+        let globals = self.scopes[0].clone();
+
+        for stmt in program {
+            self.stmt(stmt);
+        }
+
+        self.take_errors().inspect_err(|_| self.scopes[0] = globals)
+    }
+
+    /// Checks an expression, returning its type.
+    pub fn check_expression(&mut self, expr: &Expr) -> Result<Type, Vec<TypeError>> {
+        let ty = self.expr(expr);
+        self.take_errors().map(|_| ty)
+    }
+
+    fn take_errors(&mut self) -> Result<(), Vec<TypeError>> {
+        match std::mem::take(&mut self.errors) {
+            errors if errors.is_empty() => Ok(()),
+            errors => Err(errors),
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::Expression(expr) => {
+                self.expr(expr);
+            }
+            StmtKind::Var(decl) => {
+                let declared = decl.ty.as_ref().map(|ty| self.resolve(ty));
+                let initializer = decl
+                    .initializer
+                    .as_ref()
+                    .map(|expr| (self.expr(expr), expr.span));
+
+                if let Some(declared) = &declared {
+                    match &initializer {
+                        Some((ty, span)) => self.expect(declared, ty, *span),
+                        // Variables without an initializer start out null.
+                        None => self.expect(declared, &Type::Null, decl.name.span),
+                    }
+                }
+
+                let ty = match (declared, initializer, decl.mutability) {
+                    (Some(declared), _, _) => declared,
+                    (None, Some((ty, _)), Mutability::Const) => ty,
+                    (None, _, _) => Type::Any,
+                };
+
+                self.define(&decl.name.name, ty);
+            }
+            StmtKind::Fn(function) => {
+                let name = &function.name.as_ref().expect("declared fn has a name").name;
+                let ty = self.function_type(function);
+
+                // Defined before checking the body so that it can call itself.
+                self.define(name, ty);
+                self.function_body(function, stmt.span);
+            }
+            StmtKind::Block(stmts) => {
+                self.scopes.push(HashMap::new());
+                for stmt in stmts {
+                    self.stmt(stmt);
+                }
+                self.scopes.pop();
+            }
+            StmtKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.condition(condition);
+                self.stmt(then_branch);
+
+                if let Some(else_branch) = else_branch {
+                    self.stmt(else_branch);
+                }
+            }
+            StmtKind::While { condition, body } => {
+                self.condition(condition);
+                self.stmt(body);
+            }
+            StmtKind::For {
+                initializer,
+                condition,
+                increment,
+                body,
+            } => {
+                self.scopes.push(HashMap::new());
+
+                if let Some(initializer) = initializer {
+                    self.stmt(initializer);
+                }
+                if let Some(condition) = condition {
+                    self.condition(condition);
+                }
+                if let Some(increment) = increment {
+                    self.expr(increment);
+                }
+                self.stmt(body);
+
+                self.scopes.pop();
+            }
+            StmtKind::Break | StmtKind::Continue => {}
+            StmtKind::Return(value) => {
+                let (ty, span) = match value {
+                    Some(expr) => (self.expr(expr), expr.span),
+                    None => (Type::Null, stmt.span),
+                };
+                let expected = self.return_types.last().cloned().unwrap_or(Type::Any);
+
+                self.expect(&expected, &ty, span);
+            }
+        }
+    }
+
+    /// The type of a function from its annotations. Parameters without an
+    /// annotation are `any`, and so is the return type when it is missing.
+    fn function_type(&mut self, function: &Function) -> Type {
+        let params = function
+            .params
+            .iter()
+            .map(|param| self.resolve_optional(param.ty.as_ref()))
+            .collect();
+
+        Type::Function {
+            params: Some(params),
+            ret: Box::new(self.resolve_optional(function.return_type.as_ref())),
+        }
+    }
+
+    /// Checks the body of a function spanning `span`, including that it does
+    /// not reach its closing brace when it has to return a value.
+    fn function_body(&mut self, function: &Function, span: Span) {
+        let params = function
+            .params
+            .iter()
+            .map(|param| {
+                (
+                    param.name.name.clone(),
+                    self.resolve_optional(param.ty.as_ref()),
+                )
+            })
+            .collect();
+        let ret = self.resolve_optional(function.return_type.as_ref());
+
+        self.scopes.push(params);
+        self.return_types.push(ret.clone());
+
+        for stmt in &function.body {
+            self.stmt(stmt);
+        }
+
+        self.return_types.pop();
+        self.scopes.pop();
+
+        // Falling off the end of a function returns null.
+        if !function.body.iter().any(always_returns) {
+            self.expect(&ret, &Type::Null, Span::new(span.end() - 1, 1));
+        }
+    }
+
+    fn condition(&mut self, condition: &Expr) {
+        let ty = self.expr(condition);
+        self.expect(&Type::Bool, &ty, condition.span);
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Type {
+        match &expr.kind {
+            ExprKind::Literal(literal) => match literal {
+                Literal::Int(_) => Type::Int,
+                Literal::Float(_) => Type::Float,
+                Literal::String(_) => Type::String,
+                Literal::Bool(_) => Type::Bool,
+                Literal::Null => Type::Null,
+            },
+            ExprKind::Variable(name) => self.lookup(name).unwrap_or(Type::Any),
+            ExprKind::Assign { target, value } => {
+                let ty = self.expr(value);
+
+                if let Some(expected) = self.lookup(&target.name) {
+                    self.expect(&expected, &ty, value.span);
+                }
+
+                ty
+            }
+            ExprKind::Grouping(inner) => self.expr(inner),
+            ExprKind::Call { callee, args } => {
+                let callee_ty = self.expr(callee);
+                let arg_tys: Vec<_> = args.iter().map(|arg| self.expr(arg)).collect();
+
+                match callee_ty {
+                    Type::Any => Type::Any,
+                    Type::Function { params, ret } => {
+                        match params {
+                            Some(params) if params.len() != args.len() => {
+                                self.errors.push(TypeError::ArityMismatch {
+                                    expected: params.len(),
+                                    found: args.len(),
+                                    span: expr.span,
+                                });
+                            }
+                            Some(params) => {
+                                for ((param, arg_ty), arg) in params.iter().zip(&arg_tys).zip(args)
+                                {
+                                    self.expect(param, arg_ty, arg.span);
+                                }
+                            }
+                            None => {}
+                        }
+
+                        *ret
+                    }
+                    found => {
+                        self.errors.push(TypeError::NotCallable {
+                            found,
+                            span: expr.span,
+                        });
+                        Type::Any
+                    }
+                }
+            }
+            ExprKind::Function(function) => {
+                let ty = self.function_type(function);
+                self.function_body(function, expr.span);
+                ty
+            }
+            ExprKind::Interpolation(parts) => {
+                for part in parts {
+                    self.expr(part);
+                }
+                Type::String
+            }
+            ExprKind::Unary { op, expr: operand } => {
+                let ty = self.expr(operand);
+
+                match (op, ty) {
+                    (UnaryOp::Negate, ty @ (Type::Int | Type::Float | Type::Any)) => ty,
+                    (UnaryOp::Not, Type::Bool | Type::Any) => Type::Bool,
+                    (op, operand) => {
+                        self.errors.push(TypeError::InvalidUnaryOperand {
+                            op: op.as_str(),
+                            operand,
+                            span: expr.span,
+                        });
+                        Type::Any
+                    }
+                }
+            }
+            ExprKind::Binary { op, left, right } => {
+                let left = self.expr(left);
+                let right = self.expr(right);
+
+                match binary_type(*op, &left, &right) {
+                    Some(ty) => ty,
+                    None => {
+                        self.errors.push(TypeError::InvalidBinaryOperands {
+                            op: op.as_str(),
+                            left,
+                            right,
+                            span: expr.span,
+                        });
+                        Type::Any
+                    }
+                }
+            }
+            ExprKind::Logical { left, right, .. } => {
+                self.condition(left);
+                self.condition(right);
+                Type::Bool
+            }
+        }
+    }
+
+    /// Reports a mismatch if a value of type `found` cannot be used where
+    /// `expected` is needed.
+    fn expect(&mut self, expected: &Type, found: &Type, span: Span) {
+        if !expected.accepts(found) {
+            self.errors.push(TypeError::Mismatch {
+                expected: expected.clone(),
+                found: found.clone(),
+                span,
+            });
+        }
+    }
+
+    fn resolve_optional(&mut self, ty: Option<&TypeExpr>) -> Type {
+        ty.map_or(Type::Any, |ty| self.resolve(ty))
+    }
+
+    /// The type named by an annotation. Unknown names are reported and
+    /// treated as `any`.
+    fn resolve(&mut self, ty: &TypeExpr) -> Type {
+        match &ty.kind {
+            TypeExprKind::Name(name) => match name.as_str() {
+                "any" => Type::Any,
+                "int" => Type::Int,
+                "float" => Type::Float,
+                "string" => Type::String,
+                "bool" => Type::Bool,
+                "null" => Type::Null,
+                _ => {
+                    self.errors.push(TypeError::UnknownType {
+                        name: name.clone(),
+                        span: ty.span,
+                    });
+                    Type::Any
+                }
+            },
+            TypeExprKind::Function { params, ret } => Type::Function {
+                params: Some(params.iter().map(|param| self.resolve(param)).collect()),
+                ret: Box::new(match ret {
+                    Some(ret) => self.resolve(ret),
+                    None => Type::Null,
+                }),
+            },
+        }
+    }
+
+    fn define(&mut self, name: &str, ty: Type) {
+        self.scopes
+            .last_mut()
+            .expect("there is always a global scope")
+            .insert(name.to_string(), ty);
+    }
+
+    /// The type of a variable, or `None` if it is not declared in any scope
+    /// the checker knows about.
+    fn lookup(&self, name: &str) -> Option<Type> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+    }
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks the types in a program.
+pub fn check(program: &[Stmt]) -> Result<(), Vec<TypeError>> {
+    TypeChecker::new().check(program)
+}
+
+/// Checks if running `stmt` always ends in a `return`, or never finishes.
+fn always_returns(stmt: &Stmt) -> bool {
+    match &stmt.kind {
+        StmtKind::Return(_) => true,
+        StmtKind::Block(stmts) => stmts.iter().any(always_returns),
+        StmtKind::If {
+            then_branch,
+            else_branch: Some(else_branch),
+            ..
+        } => always_returns(then_branch) && always_returns(else_branch),
+        // Loops that can only be left by returning.
+        StmtKind::While { condition, body } => {
+            matches!(condition.kind, ExprKind::Literal(Literal::Bool(true))) && !breaks(body)
+        }
+        StmtKind::For {
+            condition: None,
+            body,
+            ..
+        } => !breaks(body),
+        _ => false,
+    }
+}
+
+/// Checks if `stmt` contains a `break` out of the loop it is the body of.
+fn breaks(stmt: &Stmt) -> bool {
+    match &stmt.kind {
+        StmtKind::Break => true,
+        StmtKind::Block(stmts) => stmts.iter().any(breaks),
+        StmtKind::If {
+            then_branch,
+            else_branch,
+            ..
+        } => breaks(then_branch) || else_branch.as_deref().is_some_and(breaks),
+        _ => false,
+    }
+}
+
+/// The type of a binary expression, or `None` if the operator cannot be
+/// applied to operands of these types.
+fn binary_type(op: BinaryOp, left: &Type, right: &Type) -> Option<Type> {
+    let comparison = matches!(
+        op,
+        BinaryOp::Equal
+            | BinaryOp::NotEqual
+            | BinaryOp::Less
+            | BinaryOp::LessEqual
+            | BinaryOp::Greater
+            | BinaryOp::GreaterEqual
+    );
+
+    match (op, left, right) {
+        (BinaryOp::Equal | BinaryOp::NotEqual, _, _) => Some(Type::Bool),
+        (_, Type::Any, _) | (_, _, Type::Any) if comparison => Some(Type::Bool),
+        (_, Type::Any, _) | (_, _, Type::Any) => Some(Type::Any),
+        (_, Type::Int, Type::Int) if comparison => Some(Type::Bool),
+        (_, Type::Int, Type::Int) => Some(Type::Int),
+        (BinaryOp::Add, Type::String, Type::String) => Some(Type::String),
+        (_, Type::String, Type::String) if comparison => Some(Type::Bool),
+        (_, left, right) if left.is_number() && right.is_number() => {
+            Some(if comparison { Type::Bool } else { Type::Float })
+        }
+        _ => None,
+    }
+}
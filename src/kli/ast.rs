@@ -0,0 +1,274 @@
+use std::{io::Write, process::ExitCode};
+
+use kitty_lang_ast::{
+    expr::{Expr, ExprKind, Ident, Literal},
+    sexpr,
+    span::Span,
+    stmt::{Function, Param, Stmt, StmtKind},
+    types::{TypeExpr, TypeExprKind},
+};
+use kitty_lang_interpreter::parser::Parser;
+
+use crate::{
+    OutputFormat, json::Json, parse_file_args, read_source, report, usage_error, write_output,
+};
+
+/// Prints the syntax tree parsed from the file named in `args`.
+pub fn ast(args: &[String]) -> ExitCode {
+    let (path, format) = match parse_file_args(args) {
+        Ok(parsed) => parsed,
+        Err(message) => return usage_error(&message),
+    };
+
+    let Some(source) = read_source(&path) else {
+        return ExitCode::FAILURE;
+    };
+
+    let program = match Parser::new(source.text()).parse_program() {
+        Ok(program) => program,
+        Err(errors) => {
+            for error in errors {
+                report(&source, &error);
+            }
+
+            return ExitCode::FAILURE;
+        }
+    };
+
+    write_output(|out| match format {
+        OutputFormat::Text => write!(out, "{}", sexpr::program(&program)),
+        OutputFormat::Json => writeln!(out, "{}", program_json(&program).to_pretty_string()),
+    })
+}
+
+/// Converts a program to JSON. Every node is an object with a `kind` naming
+/// the node type and a `span` locating it in the source; the remaining fields
+/// depend on the kind.
+fn program_json(stmts: &[Stmt]) -> Json {
+    Json::Object(vec![
+        ("kind", Json::str("Program")),
+        ("body", stmts_json(stmts)),
+    ])
+}
+
+fn stmts_json(stmts: &[Stmt]) -> Json {
+    Json::Array(stmts.iter().map(stmt_json).collect())
+}
+
+fn node(kind: &str, span: Span, mut fields: Vec<(&'static str, Json)>) -> Json {
+    let mut node = vec![("kind", Json::str(kind)), ("span", span_json(span))];
+    node.append(&mut fields);
+    Json::Object(node)
+}
+
+fn span_json(span: Span) -> Json {
+    Json::Object(vec![
+        ("index", Json::Int(span.index as i64)),
+        ("length", Json::Int(span.length as i64)),
+    ])
+}
+
+fn ident_json(ident: &Ident) -> Json {
+    node("Ident", ident.span, vec![("name", Json::str(&ident.name))])
+}
+
+fn param_json(param: &Param) -> Json {
+    let span = match &param.ty {
+        Some(ty) => param.name.span.to(ty.span),
+        None => param.name.span,
+    };
+
+    node(
+        "Param",
+        span,
+        vec![
+            ("name", ident_json(&param.name)),
+            ("type", optional_type_json(param.ty.as_ref())),
+        ],
+    )
+}
+
+fn type_json(ty: &TypeExpr) -> Json {
+    match &ty.kind {
+        TypeExprKind::Name(name) => node("NameType", ty.span, vec![("name", Json::str(name))]),
+        TypeExprKind::Function { params, ret } => node(
+            "FunctionType",
+            ty.span,
+            vec![
+                (
+                    "params",
+                    Json::Array(params.iter().map(type_json).collect()),
+                ),
+                ("returns", optional_type_json(ret.as_deref())),
+            ],
+        ),
+    }
+}
+
+fn optional_type_json(ty: Option<&TypeExpr>) -> Json {
+    ty.map(type_json).unwrap_or(Json::Null)
+}
+
+fn optional_expr_json(expr: Option<&Expr>) -> Json {
+    expr.map(expr_json).unwrap_or(Json::Null)
+}
+
+fn optional_string_json(text: Option<&str>) -> Json {
+    text.map(Json::str).unwrap_or(Json::Null)
+}
+
+fn function_json(kind: &str, span: Span, function: &Function) -> Json {
+    node(
+        kind,
+        span,
+        vec![
+            (
+                "name",
+                function.name.as_ref().map(ident_json).unwrap_or(Json::Null),
+            ),
+            (
+                "params",
+                Json::Array(function.params.iter().map(param_json).collect()),
+            ),
+            ("returns", optional_type_json(function.return_type.as_ref())),
+            ("body", stmts_json(&function.body)),
+            ("doc", optional_string_json(function.doc.as_deref())),
+        ],
+    )
+}
+
+fn stmt_json(stmt: &Stmt) -> Json {
+    let span = stmt.span;
+
+    match &stmt.kind {
+        StmtKind::Expression(expr) => node("ExpressionStmt", span, vec![("expr", expr_json(expr))]),
+        StmtKind::Var(decl) => node(
+            "VarDecl",
+            span,
+            vec![
+                ("mutability", Json::str(decl.mutability.as_str())),
+                ("name", ident_json(&decl.name)),
+                ("type", optional_type_json(decl.ty.as_ref())),
+                ("initializer", optional_expr_json(decl.initializer.as_ref())),
+                ("doc", optional_string_json(decl.doc.as_deref())),
+            ],
+        ),
+        StmtKind::Fn(function) => function_json("FnDecl", span, function),
+        StmtKind::Block(stmts) => node("Block", span, vec![("body", stmts_json(stmts))]),
+        StmtKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => node(
+            "If",
+            span,
+            vec![
+                ("condition", expr_json(condition)),
+                ("then", stmt_json(then_branch)),
+                (
+                    "else",
+                    else_branch.as_deref().map(stmt_json).unwrap_or(Json::Null),
+                ),
+            ],
+        ),
+        StmtKind::While { condition, body } => node(
+            "While",
+            span,
+            vec![
+                ("condition", expr_json(condition)),
+                ("body", stmt_json(body)),
+            ],
+        ),
+        StmtKind::For {
+            initializer,
+            condition,
+            increment,
+            body,
+        } => node(
+            "For",
+            span,
+            vec![
+                (
+                    "initializer",
+                    initializer.as_deref().map(stmt_json).unwrap_or(Json::Null),
+                ),
+                ("condition", optional_expr_json(condition.as_ref())),
+                ("increment", optional_expr_json(increment.as_ref())),
+                ("body", stmt_json(body)),
+            ],
+        ),
+        StmtKind::Break => node("Break", span, vec![]),
+        StmtKind::Continue => node("Continue", span, vec![]),
+        StmtKind::Return(value) => node(
+            "Return",
+            span,
+            vec![("value", optional_expr_json(value.as_ref()))],
+        ),
+    }
+}
+
+fn expr_json(expr: &Expr) -> Json {
+    let span = expr.span;
+
+    match &expr.kind {
+        ExprKind::Literal(literal) => {
+            let (ty, value) = match literal {
+                Literal::Int(v) => ("int", Json::Int(*v)),
+                Literal::Float(v) => ("float", Json::Float(*v)),
+                Literal::String(v) => ("string", Json::str(v)),
+                Literal::Bool(v) => ("bool", Json::Bool(*v)),
+                Literal::Null => ("null", Json::Null),
+            };
+
+            node(
+                "Literal",
+                span,
+                vec![("type", Json::str(ty)), ("value", value)],
+            )
+        }
+        ExprKind::Variable(name) => node("Variable", span, vec![("name", Json::str(name))]),
+        ExprKind::Assign { target, value } => node(
+            "Assign",
+            span,
+            vec![("target", ident_json(target)), ("value", expr_json(value))],
+        ),
+        ExprKind::Grouping(expr) => node("Grouping", span, vec![("expr", expr_json(expr))]),
+        ExprKind::Call { callee, args } => node(
+            "Call",
+            span,
+            vec![
+                ("callee", expr_json(callee)),
+                ("args", Json::Array(args.iter().map(expr_json).collect())),
+            ],
+        ),
+        ExprKind::Function(function) => function_json("Function", span, function),
+        ExprKind::Interpolation(parts) => node(
+            "Interpolation",
+            span,
+            vec![("parts", Json::Array(parts.iter().map(expr_json).collect()))],
+        ),
+        ExprKind::Unary { op, expr } => node(
+            "Unary",
+            span,
+            vec![("op", Json::str(op.as_str())), ("expr", expr_json(expr))],
+        ),
+        ExprKind::Binary { op, left, right } => node(
+            "Binary",
+            span,
+            vec![
+                ("op", Json::str(op.as_str())),
+                ("left", expr_json(left)),
+                ("right", expr_json(right)),
+            ],
+        ),
+        ExprKind::Logical { op, left, right } => node(
+            "Logical",
+            span,
+            vec![
+                ("op", Json::str(op.as_str())),
+                ("left", expr_json(left)),
+                ("right", expr_json(right)),
+            ],
+        ),
+    }
+}
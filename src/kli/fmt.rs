@@ -0,0 +1,66 @@
+use std::process::ExitCode;
+
+use kitty_lang_interpreter::formatter::Formatter;
+
+use crate::{read_source, report, usage_error};
+
+/// Formats the files named in `args` in place, or with `--check` reports the
+/// files that are not formatted without changing them.
+pub fn fmt(args: &[String]) -> ExitCode {
+    let mut check = false;
+    let mut paths = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            arg if arg.starts_with("--") => {
+                return usage_error(&format!("unknown option `{arg}`"));
+            }
+            _ => paths.push(arg),
+        }
+    }
+
+    if paths.is_empty() {
+        return usage_error("missing file");
+    }
+
+    let formatter = Formatter::new();
+    let mut failed = false;
+
+    for path in paths {
+        let Some(source) = read_source(path) else {
+            failed = true;
+            continue;
+        };
+
+        let formatted = match formatter.format(source.text()) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                for error in errors {
+                    report(&source, &error);
+                }
+
+                failed = true;
+                continue;
+            }
+        };
+
+        if formatted == source.text() {
+            continue;
+        }
+
+        if check {
+            println!("{path} is not formatted");
+            failed = true;
+        } else if let Err(e) = std::fs::write(path, formatted) {
+            eprintln!("error: could not write `{path}`: {e}");
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,140 @@
+mod ast;
+mod fmt;
+mod json;
+mod repl;
+mod run;
+mod tokens;
+
+use std::{
+    io::{self, IsTerminal, StdoutLock, Write},
+    process::ExitCode,
+};
+
+use kitty_lang_interpreter::{
+    diagnostics::{Diagnostic, Renderer},
+    source::SourceFile,
+};
+
+const USAGE: &str = "\
+Usage: kli [command] [args...]
+
+Starts an interactive session when no command is given.
+
+Commands:
+  run <file> [args...]  Run a kitty program, passing any remaining arguments
+                        to the program
+  tokens <file>         Print the lexemes scanned from a kitty source file
+  ast <file>            Print the syntax tree parsed from a kitty source file
+  fmt [--check] <files...>
+                        Format kitty source files in place, or with
+                        `--check` list the files that are not formatted
+  help                  Print this message
+
+Options:
+  --format <text|json>  Output format for `tokens` and `ast` (default:
+                        text)";
+
+/// Exit code for invalid command line usage.
+const EXIT_USAGE: u8 = 2;
+
+pub fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("run") => run::run(&args[1..]),
+        Some("tokens") => tokens::tokens(&args[1..]),
+        Some("ast") => ast::ast(&args[1..]),
+        Some("fmt") => fmt::fmt(&args[1..]),
+        Some("help" | "-h" | "--help") => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Some(command) => usage_error(&format!("unknown command `{command}`")),
+        None => repl::repl(),
+    }
+}
+
+/// How commands that dump information about a source file print it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// Human readable text.
+    Text,
+    /// Machine readable JSON.
+    Json,
+}
+
+/// Parses the arguments for commands that take a single file and an optional
+/// `--format` flag.
+fn parse_file_args(args: &[String]) -> Result<(String, OutputFormat), String> {
+    let mut path = None;
+    let mut format = OutputFormat::Text;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let format_name = match arg.as_str() {
+            "--format" => Some(args.next().ok_or("missing value for `--format`")?.as_str()),
+            arg => arg.strip_prefix("--format="),
+        };
+
+        if let Some(format_name) = format_name {
+            format = match format_name {
+                "text" => OutputFormat::Text,
+                "json" => OutputFormat::Json,
+                other => return Err(format!("unknown format `{other}`")),
+            };
+        } else if arg.starts_with("--") {
+            return Err(format!("unknown option `{arg}`"));
+        } else if path.replace(arg.clone()).is_some() {
+            return Err(format!("unexpected argument `{arg}`"));
+        }
+    }
+
+    Ok((path.ok_or("missing file")?, format))
+}
+
+/// Reports a problem with the command line arguments and prints usage help.
+fn usage_error(message: &str) -> ExitCode {
+    eprintln!("error: {message}\n\n{USAGE}");
+    ExitCode::from(EXIT_USAGE)
+}
+
+/// Reads the source file at `path`, reporting an error if it cannot be read.
+fn read_source(path: &str) -> Option<SourceFile> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Some(SourceFile::new(path, text)),
+        Err(e) => {
+            eprintln!("error: could not read `{path}`: {e}");
+            None
+        }
+    }
+}
+
+/// Writes a command's output to a locked stdout. A reader that stops early,
+/// such as `head`, closes the pipe; that ends the output quietly rather than
+/// being reported as an error.
+fn write_output(write: impl FnOnce(&mut StdoutLock) -> io::Result<()>) -> ExitCode {
+    let mut stdout = io::stdout().lock();
+
+    match write(&mut stdout).and_then(|()| stdout.flush()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: could not write output: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Reports a problem found in a source file to stderr. Output is colored when
+/// stderr is a terminal, unless the `NO_COLOR` environment variable is set.
+fn report(source: &SourceFile, diagnostic: impl Into<Diagnostic>) {
+    let use_color = std::io::stderr().is_terminal()
+        && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty());
+    let renderer = if use_color {
+        Renderer::colored()
+    } else {
+        Renderer::plain()
+    };
+
+    eprintln!("{}", renderer.render(source, &diagnostic.into()));
+}
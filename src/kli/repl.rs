@@ -0,0 +1,174 @@
+use std::{
+    io::{BufRead, Write},
+    process::ExitCode,
+};
+
+use kitty_lang_interpreter::{
+    identifiers,
+    interpreter::{Interpreter, Value},
+    parser::Parser,
+    scanner::Scanner,
+    source::SourceFile,
+    tokens::{InvalidTokenReason, Token},
+    typeck::TypeChecker,
+};
+
+use crate::{report, tokens, write_output};
+
+const HELP: &str = "\
+Enter kitty statements or expressions. The value of an expression is printed
+after it is evaluated. Input continues onto the next line while there are
+unclosed braces, parentheses or strings.
+
+Commands:
+  :tokens <code>  Print the lexemes scanned from <code>
+  :reset          Forget all declared variables and functions
+  :help           Print this message
+  :quit           Exit the session";
+
+/// Name used for the source of REPL input in error messages.
+const REPL_SOURCE_NAME: &str = "<repl>";
+
+/// Runs an interactive read-eval-print loop on stdin until `:quit` or the end
+/// of input.
+pub fn repl() -> ExitCode {
+    let mut interpreter = Interpreter::new();
+    let mut checker = TypeChecker::new();
+    let mut input = String::new();
+    let mut lines = std::io::stdin().lock().lines();
+
+    loop {
+        print!("{}", if input.is_empty() { "> " } else { "... " });
+        let _ = std::io::stdout().flush();
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(e)) => {
+                eprintln!("error: could not read input: {e}");
+                return ExitCode::FAILURE;
+            }
+            None => break,
+        };
+
+        if input.is_empty() {
+            match line.trim() {
+                "" => continue,
+                ":quit" => break,
+                ":reset" => {
+                    interpreter = Interpreter::new();
+                    checker = TypeChecker::new();
+                    continue;
+                }
+                ":help" => {
+                    println!("{HELP}");
+                    continue;
+                }
+                command if command.starts_with(':') => {
+                    run_command(command);
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        input.push_str(&line);
+        input.push('\n');
+
+        if is_incomplete(&input) {
+            continue;
+        }
+
+        eval_input(&mut interpreter, &mut checker, &input);
+        input.clear();
+    }
+
+    println!();
+    ExitCode::SUCCESS
+}
+
+fn run_command(command: &str) {
+    let (name, arg) = command.split_once(' ').unwrap_or((command, ""));
+
+    match name {
+        ":tokens" => {
+            let source = SourceFile::new(REPL_SOURCE_NAME, arg);
+            let lexemes: Vec<_> = Scanner::new(arg).collect();
+            write_output(|out| tokens::print_text(out, &source, &lexemes));
+        }
+        _ => eprintln!("error: unknown command `{name}`, try `:help`"),
+    }
+}
+
+/// Evaluates one complete entry. Bare expressions have their value printed,
+/// otherwise the input is run as a sequence of statements. Nothing is run if
+/// the entry has type errors.
+fn eval_input(interpreter: &mut Interpreter, checker: &mut TypeChecker, input: &str) {
+    let source = SourceFile::new(REPL_SOURCE_NAME, input);
+
+    for warning in identifiers::warnings(input) {
+        report(&source, warning);
+    }
+
+    if let Ok(expr) = Parser::new(input).parse_expression() {
+        if let Err(errors) = checker.check_expression(&expr) {
+            for error in errors {
+                report(&source, &error);
+            }
+
+            return;
+        }
+
+        match interpreter.evaluate(&expr) {
+            Ok(Value::Null) => {}
+            Ok(value) => println!("{value}"),
+            Err(error) => report(&source, &error),
+        }
+
+        return;
+    }
+
+    match Parser::new(input).parse_program() {
+        Ok(program) => {
+            if let Err(errors) = checker.check(&program) {
+                for error in errors {
+                    report(&source, &error);
+                }
+            } else if let Err(error) = interpreter.run(&program) {
+                report(&source, &error);
+            }
+        }
+        Err(errors) => {
+            for error in errors {
+                report(&source, &error);
+            }
+        }
+    }
+}
+
+/// Checks if the input has unclosed braces, parentheses or strings and so more
+/// lines should be read before evaluating it.
+fn is_incomplete(input: &str) -> bool {
+    let mut depth: isize = 0;
+
+    for lexeme in Scanner::new(input) {
+        match lexeme.token {
+            Token::LeftBrace | Token::LeftParen | Token::LeftBracket | Token::StringStart => {
+                depth += 1
+            }
+            Token::RightBrace | Token::RightParen | Token::RightBracket | Token::StringEnd => {
+                depth -= 1
+            }
+            // Only strings that can span lines run on to the end of the input
+            // when unterminated. Other strings end at the line break.
+            Token::Invalid(InvalidTokenReason::UnterminatedString)
+                if lexeme.byte_range().end == input.len() =>
+            {
+                return true;
+            }
+            Token::Invalid(InvalidTokenReason::UnterminatedComment) => return true,
+            _ => {}
+        }
+    }
+
+    depth > 0
+}
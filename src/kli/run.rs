@@ -0,0 +1,54 @@
+use std::process::ExitCode;
+
+use kitty_lang_interpreter::{identifiers, interpreter::Interpreter, parser::Parser, typeck};
+
+use crate::{read_source, report, usage_error};
+
+/// Runs the kitty program named by the first argument. Remaining arguments
+/// are made available to the program.
+pub fn run(args: &[String]) -> ExitCode {
+    let Some((path, script_args)) = args.split_first() else {
+        return usage_error("missing file to run");
+    };
+
+    let Some(source) = read_source(path) else {
+        return ExitCode::FAILURE;
+    };
+
+    for warning in identifiers::warnings(source.text()) {
+        report(&source, warning);
+    }
+
+    let program = match Parser::new(source.text()).parse_program() {
+        Ok(program) => program,
+        Err(errors) => {
+            for error in errors {
+                report(&source, &error);
+            }
+
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(errors) = typeck::check(&program) {
+        for error in errors {
+            report(&source, &error);
+        }
+
+        return ExitCode::FAILURE;
+    }
+
+    let mut interpreter = Interpreter::new();
+    interpreter.set_args(script_args.to_vec());
+
+    let result = interpreter.run(&program);
+    let _ = interpreter.output().flush();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            report(&source, &error);
+            ExitCode::FAILURE
+        }
+    }
+}
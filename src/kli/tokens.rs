@@ -0,0 +1,80 @@
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+};
+
+use kitty_lang_interpreter::{
+    scanner::{Lexeme, Scanner},
+    source::SourceFile,
+    tokens::Token,
+};
+
+use crate::{OutputFormat, json, parse_file_args, read_source, usage_error, write_output};
+
+/// Prints every lexeme scanned from the file named in `args`.
+pub fn tokens(args: &[String]) -> ExitCode {
+    let (path, format) = match parse_file_args(args) {
+        Ok(parsed) => parsed,
+        Err(message) => return usage_error(&message),
+    };
+
+    let Some(source) = read_source(&path) else {
+        return ExitCode::FAILURE;
+    };
+
+    let lexemes: Vec<_> = Scanner::new(source.text()).collect();
+
+    write_output(|out| match format {
+        OutputFormat::Text => print_text(out, &source, &lexemes),
+        OutputFormat::Json => print_json(out, &source, &lexemes),
+    })
+}
+
+/// Prints each lexeme on its own line as `line:column Token "text"`.
+pub fn print_text(out: &mut impl Write, source: &SourceFile, lexemes: &[Lexeme]) -> io::Result<()> {
+    for lexeme in lexemes {
+        writeln!(
+            out,
+            "{} {:?} {:?}",
+            source.location(lexeme.index),
+            lexeme.token,
+            lexeme.text(source.text())
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Prints the lexemes as a JSON array with one lexeme object per line.
+fn print_json(out: &mut impl Write, source: &SourceFile, lexemes: &[Lexeme]) -> io::Result<()> {
+    writeln!(out, "[")?;
+
+    for (i, lexeme) in lexemes.iter().enumerate() {
+        let location = source.location(lexeme.index);
+        let (token, reason) = match lexeme.token {
+            Token::Invalid(reason) => ("Invalid".to_string(), Some(format!("{reason:?}"))),
+            token => (format!("{token:?}"), None),
+        };
+
+        write!(
+            out,
+            "  {{\"token\": {}, \"line\": {}, \"column\": {}, \"index\": {}, \"length\": {}, \"byte_index\": {}, \"byte_length\": {}, \"text\": {}",
+            json::string(&token),
+            location.line,
+            location.column,
+            lexeme.index,
+            lexeme.length,
+            lexeme.byte_index,
+            lexeme.byte_length,
+            json::string(lexeme.text(source.text()))
+        )?;
+
+        if let Some(reason) = reason {
+            write!(out, ", \"reason\": {}", json::string(&reason))?;
+        }
+
+        writeln!(out, "}}{}", if i + 1 < lexemes.len() { "," } else { "" })?;
+    }
+
+    writeln!(out, "]")
+}
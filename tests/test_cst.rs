@@ -0,0 +1,349 @@
+use kitty_lang_ast::cst::{
+    SyntaxKind,
+    green::{GreenNodeBuilder, GreenToken},
+    nodes::{AstNode, Expr, SourceFile, Stmt, Type},
+    red::SyntaxNode,
+};
+use kitty_lang_interpreter::{cst_parser::CstParser, parser::ParseError, tokens::Token};
+
+fn parse(source: &str) -> SourceFile {
+    let (file, errors) = CstParser::new(source).parse();
+    assert_eq!(errors, vec![], "unexpected errors parsing {source:?}");
+    file
+}
+
+fn kinds(node: &SyntaxNode) -> Vec<SyntaxKind> {
+    node.descendants().map(|n| n.kind()).collect()
+}
+
+#[test]
+fn cst_prints_back_the_source_exactly() {
+    let sources = [
+        "",
+        "  \n// just a comment\n",
+        "var a = 1 +  2 * (3 - x); // trailing\n",
+        "/// Adds.\nfn add(a, b) {\n\treturn a + b;\n}\r\nprint(add(1, 2));",
+        "for (var i = 0; i < 10; i = i + 1) { if (i == 5) break; else continue; }",
+        "var s = \"a {x + 1} b {y}\";\nwhile (not done and x or y) {}",
+        "const f = fn (x) { /* block */ return -x; };",
+        "var x : int=1;\nfn f(a:float, g: fn(int)->null) ->  string { return \"\"; }",
+    ];
+
+    for source in sources {
+        assert_eq!(parse(source).syntax().text(), source);
+    }
+}
+
+#[test]
+fn cst_nodes_cover_their_source_text() {
+    let source = "var answer = 6 * 7;";
+    let file = parse(source);
+
+    assert_eq!(file.syntax().text_range(), 0..source.len());
+    assert_eq!(
+        kinds(file.syntax()),
+        vec![
+            SyntaxKind::SourceFile,
+            SyntaxKind::VarDecl,
+            SyntaxKind::Name,
+            SyntaxKind::BinaryExpr,
+            SyntaxKind::Literal,
+            SyntaxKind::Literal,
+        ]
+    );
+
+    let binary = file
+        .syntax()
+        .descendants()
+        .find(|n| n.kind() == SyntaxKind::BinaryExpr)
+        .unwrap();
+    assert_eq!(&source[binary.text_range()], "6 * 7");
+    assert_eq!(binary.parent().unwrap().kind(), SyntaxKind::VarDecl);
+}
+
+#[test]
+fn cst_typed_views_expose_declarations() {
+    let file =
+        parse("/// Doubles `x`.\n/// Twice.\nfn double(x) { return x * 2; }\nvar y = double(21);");
+    let stmts: Vec<_> = file.stmts().collect();
+    assert_eq!(stmts.len(), 2);
+
+    let Stmt::FnDecl(double) = &stmts[0] else {
+        panic!("expected a function, got {:?}", stmts[0]);
+    };
+    assert_eq!(double.name().unwrap().ident().unwrap().text(), "double");
+    assert_eq!(
+        double
+            .param_list()
+            .unwrap()
+            .params()
+            .map(|p| p.name().unwrap().ident().unwrap().text().to_string())
+            .collect::<Vec<_>>(),
+        vec!["x"]
+    );
+    assert_eq!(
+        double
+            .doc_comments()
+            .map(|t| t.text().to_string())
+            .collect::<Vec<_>>(),
+        vec!["/// Doubles `x`.", "/// Twice."]
+    );
+
+    let body: Vec<_> = double.body().unwrap().stmts().collect();
+    let Stmt::ReturnStmt(ret) = &body[0] else {
+        panic!("expected a return, got {:?}", body[0]);
+    };
+    let Some(Expr::BinaryExpr(product)) = ret.value() else {
+        panic!("expected a binary expression");
+    };
+    assert_eq!(product.op().unwrap().kind(), SyntaxKind::Star);
+    assert_eq!(product.lhs().unwrap().syntax().text(), "x");
+    assert_eq!(product.rhs().unwrap().syntax().text(), "2");
+
+    let Stmt::VarDecl(y) = &stmts[1] else {
+        panic!("expected a variable, got {:?}", stmts[1]);
+    };
+    assert_eq!(y.keyword().unwrap().kind(), SyntaxKind::Var);
+    assert_eq!(y.doc_comments().count(), 0);
+    let Some(Expr::CallExpr(call)) = y.initializer() else {
+        panic!("expected a call");
+    };
+    assert_eq!(call.callee().unwrap().syntax().text(), "double");
+    assert_eq!(call.arg_list().unwrap().args().count(), 1);
+}
+
+#[test]
+fn cst_typed_views_expose_type_annotations() {
+    let file = parse("fn f(a: int, b) -> fn(float) -> string {}\nvar x: int;");
+    let stmts: Vec<_> = file.stmts().collect();
+
+    let Stmt::FnDecl(f) = &stmts[0] else {
+        panic!("expected a function, got {:?}", stmts[0]);
+    };
+    let params: Vec<_> = f.param_list().unwrap().params().collect();
+    assert_eq!(params.len(), 2);
+    let Some(Type::NameType(int)) = params[0].ty() else {
+        panic!("expected a named type");
+    };
+    assert_eq!(int.token().unwrap().text(), "int");
+    assert!(params[1].ty().is_none());
+
+    let Some(Type::FnType(ret)) = f.ret_type().unwrap().ty() else {
+        panic!("expected a function type");
+    };
+    assert_eq!(ret.params().count(), 1);
+    assert_eq!(
+        ret.ret_type().unwrap().ty().unwrap().syntax().text(),
+        "string"
+    );
+
+    let Stmt::VarDecl(x) = &stmts[1] else {
+        panic!("expected a variable, got {:?}", stmts[1]);
+    };
+    assert_eq!(x.ty().unwrap().syntax().text(), "int");
+}
+
+#[test]
+fn cst_typed_views_expose_control_flow() {
+    let file = parse("for (i = 0; i < 3;) if (i) a = 1; else { b = 2; }");
+    let Some(Stmt::ForStmt(for_stmt)) = file.stmts().next() else {
+        panic!("expected a for loop");
+    };
+
+    assert_eq!(for_stmt.initializer().unwrap().syntax().text(), "i = 0;");
+    assert_eq!(for_stmt.condition().unwrap().syntax().text(), "i < 3");
+    assert!(for_stmt.increment().is_none());
+
+    let Some(Stmt::IfStmt(if_stmt)) = for_stmt.body() else {
+        panic!("expected an if statement");
+    };
+    assert_eq!(if_stmt.condition().unwrap().syntax().text(), "i");
+    assert_eq!(if_stmt.then_branch().unwrap().syntax().text(), "a = 1;");
+    assert_eq!(if_stmt.else_branch().unwrap().syntax().text(), "{ b = 2; }");
+}
+
+#[test]
+fn cst_puts_comments_where_they_appear() {
+    let file = parse("// header\n\n/// Docs.\nvar a = 1; // after a\n");
+    let decl = file.syntax().children().next().unwrap();
+
+    assert_eq!(decl.text(), "/// Docs.\nvar a = 1;");
+    assert_eq!(
+        file.syntax()
+            .children_with_tokens()
+            .map(|e| e.kind())
+            .collect::<Vec<_>>(),
+        vec![
+            SyntaxKind::Comment,
+            SyntaxKind::Newline,
+            SyntaxKind::Newline,
+            SyntaxKind::VarDecl,
+            SyntaxKind::Whitespace,
+            SyntaxKind::Comment,
+            SyntaxKind::Newline,
+        ]
+    );
+}
+
+#[test]
+fn cst_rename_keeps_formatting() {
+    let source = "var  count = 1;   // the count\nprint( count  +count );\n";
+    let mut root = parse(source).syntax().clone();
+
+    loop {
+        let Some(ident) = root
+            .tokens()
+            .find(|t| t.kind() == SyntaxKind::Identifier && t.text() == "count")
+        else {
+            break;
+        };
+        root = SyntaxNode::new_root(
+            ident.replace_with(GreenToken::new(SyntaxKind::Identifier, "total")),
+        );
+    }
+
+    assert_eq!(
+        root.text(),
+        "var  total = 1;   // the count\nprint( total  +total );\n"
+    );
+}
+
+#[test]
+fn cst_replacing_a_node_shares_the_rest_of_the_tree() {
+    let file = parse("var a = 1;\nvar b = 2;\n");
+    let first = file.syntax().children().next().unwrap();
+    let second = file.syntax().children().nth(1).unwrap();
+    let replacement = parse("var a = 10;").syntax().children().next().unwrap();
+
+    let root = SyntaxNode::new_root(first.replace_with(replacement.green().clone()));
+    assert_eq!(root.text(), "var a = 10;\nvar b = 2;\n");
+
+    let new_second = root.children().nth(1).unwrap();
+    assert!(std::ptr::eq(new_second.green(), second.green()));
+    assert_eq!(new_second.text_range(), 12..22);
+}
+
+#[test]
+fn cst_keeps_every_token_of_invalid_source() {
+    let sources = [
+        "var = 1;",
+        "var x = ;\nprint(x);",
+        "print(1 +);",
+        "fn f(a, { }",
+        "if (x { y; }",
+        "}}} ) ;",
+        "var while = 1;",
+        "1 = 2;",
+        "x = $ + 1;",
+        "var s = \"open {x",
+    ];
+
+    for source in sources {
+        let (file, errors) = CstParser::new(source).parse();
+        assert_eq!(file.syntax().text(), source);
+        assert!(!errors.is_empty(), "expected errors parsing {source:?}");
+    }
+}
+
+#[test]
+fn cst_binary_operands_stay_on_their_side_of_errors() {
+    let (file, _) = CstParser::new("x = ~ + 1;\ny = 1 + ;\nz = ~ and true;").parse();
+    let binaries: Vec<_> = file
+        .syntax()
+        .descendants()
+        .filter_map(|n| match Expr::cast(n) {
+            Some(Expr::BinaryExpr(binary)) => Some((binary.lhs(), binary.rhs())),
+            Some(Expr::LogicalExpr(logical)) => Some((logical.lhs(), logical.rhs())),
+            _ => None,
+        })
+        .map(|(lhs, rhs)| {
+            (
+                lhs.map(|e| e.syntax().text()),
+                rhs.map(|e| e.syntax().text()),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        binaries,
+        vec![
+            (None, Some("1".to_string())),
+            (Some("1".to_string()), None),
+            (None, Some("true".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn cst_reports_syntax_errors() {
+    let (file, errors) = CstParser::new("var x = ;\nvar y = 2;").parse();
+
+    assert_eq!(
+        errors,
+        vec![ParseError::UnexpectedToken {
+            expected: "an expression",
+            found: Token::Semicolon,
+            span: kitty_lang_ast::span::Span::new(8, 1),
+        }]
+    );
+    assert_eq!(file.stmts().count(), 2);
+
+    let (_, errors) = CstParser::new("1 = 2;").parse();
+    assert!(matches!(
+        errors.as_slice(),
+        [ParseError::InvalidAssignmentTarget { .. }]
+    ));
+}
+
+#[test]
+fn cst_wraps_stray_tokens_in_error_nodes() {
+    let (file, _) = CstParser::new("print(1); ) var a = 2;").parse();
+
+    assert_eq!(
+        file.syntax()
+            .children()
+            .map(|n| n.kind())
+            .collect::<Vec<_>>(),
+        vec![SyntaxKind::ExprStmt, SyntaxKind::Error, SyntaxKind::VarDecl]
+    );
+    assert_eq!(file.syntax().children().nth(1).unwrap().text(), ")");
+}
+
+#[test]
+fn green_node_builder_wraps_children_at_a_checkpoint() {
+    let mut builder = GreenNodeBuilder::new();
+    builder.start_node(SyntaxKind::SourceFile);
+    let checkpoint = builder.checkpoint();
+    builder.start_node(SyntaxKind::Literal);
+    builder.token(SyntaxKind::Int, "1");
+    builder.finish_node();
+    builder.start_node_at(checkpoint, SyntaxKind::BinaryExpr);
+    builder.token(SyntaxKind::Plus, "+");
+    builder.start_node(SyntaxKind::Literal);
+    builder.token(SyntaxKind::Int, "2");
+    builder.finish_node();
+    builder.finish_node();
+    builder.finish_node();
+
+    let root = SyntaxNode::new_root(builder.finish());
+    assert_eq!(root.text(), "1+2");
+    assert_eq!(
+        kinds(&root),
+        vec![
+            SyntaxKind::SourceFile,
+            SyntaxKind::BinaryExpr,
+            SyntaxKind::Literal,
+            SyntaxKind::Literal,
+        ]
+    );
+}
+
+#[test]
+fn syntax_kinds_match_tokens() {
+    assert_eq!(Token::LeftParen.syntax_kind(), SyntaxKind::LeftParen);
+    assert_eq!(Token::While.syntax_kind(), SyntaxKind::While);
+    assert_eq!(Token::DocComment.syntax_kind(), SyntaxKind::DocComment);
+    assert!(Token::Comment.syntax_kind().is_trivia());
+    assert!(Token::Int.syntax_kind().is_token());
+    assert!(!SyntaxKind::SourceFile.is_token());
+}
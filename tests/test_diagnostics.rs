@@ -0,0 +1,171 @@
+use kitty_lang_ast::span::Span;
+use kitty_lang_interpreter::{
+    diagnostics::{Diagnostic, Renderer},
+    interpreter::Interpreter,
+    parser::Parser,
+    scanner::Scanner,
+    source::SourceFile,
+};
+
+#[test]
+fn diagnostic_render_invalid_lexeme() {
+    let source = SourceFile::new("main.kitty", "var a = 1;\nvar s = \"cat;\n");
+    let diagnostic = Scanner::new(source.text())
+        .find_map(|lexeme| lexeme.diagnostic(source.text()))
+        .unwrap();
+
+    assert_eq!(
+        diagnostic.render(&source),
+        "error: unterminated string
+ --> main.kitty:2:9
+  |
+2 | var s = \"cat;
+  |         ^^^^^
+  |
+  = help: add a closing `\"` to end the string
+"
+    );
+}
+
+#[test]
+fn diagnostic_render_points_at_invalid_escape() {
+    let source = SourceFile::new("main.kitty", "var s = \"é \\q {x}\";");
+    let expected = "error: invalid escape sequence in string
+ --> main.kitty:1:12
+  |
+1 | var s = \"é \\q {x}\";
+  |            ^^
+  |
+  = help: supported escapes are `\\\"`, `\\\\`, `\\{`, `\\}`, `\\n`, `\\r`, `\\t`, `\\0` and `\\u{...}`
+";
+
+    let lexeme_diagnostic = Scanner::new(source.text())
+        .find_map(|lexeme| lexeme.diagnostic(source.text()))
+        .unwrap();
+    assert_eq!(lexeme_diagnostic.render(&source), expected);
+
+    let errors = Parser::new(source.text()).parse_program().unwrap_err();
+    assert_eq!(Diagnostic::from(&errors[0]).render(&source), expected);
+}
+
+#[test]
+fn diagnostic_render_parse_error_with_label() {
+    let source = SourceFile::new("main.kitty", "print(1 2);");
+    let errors = Parser::new(source.text()).parse_program().unwrap_err();
+
+    assert_eq!(
+        Diagnostic::from(&errors[0]).render(&source),
+        "error: expected ')' after arguments but found int
+ --> main.kitty:1:9
+  |
+1 | print(1 2);
+  |         ^ unexpected int
+"
+    );
+}
+
+#[test]
+fn diagnostic_render_number_out_of_range_help() {
+    let source = SourceFile::new("main.kitty", "print(1e999);\nprint(99999999999999999999);");
+    let errors = Parser::new(source.text()).parse_program().unwrap_err();
+
+    assert_eq!(
+        Diagnostic::from(&errors[0]).render(&source),
+        "error: number literal is out of range
+ --> main.kitty:1:7
+  |
+1 | print(1e999);
+  |       ^^^^^
+  |
+  = help: floats must be finite (at most ~1.8e308)
+"
+    );
+    assert!(
+        Diagnostic::from(&errors[1])
+            .render(&source)
+            .contains("help: ints must be between")
+    );
+}
+
+#[test]
+fn diagnostic_render_runtime_error() {
+    let source = SourceFile::new("main.kitty", "const c = 1;\nc = c + 1;");
+    let program = Parser::new(source.text()).parse_program().unwrap();
+    let error = Interpreter::with_output(Box::new(std::io::sink()))
+        .run(&program)
+        .unwrap_err();
+
+    assert_eq!(
+        Diagnostic::from(&error).render(&source),
+        "error: cannot assign to constant `c`
+ --> main.kitty:2:1
+  |
+2 | c = c + 1;
+  | ^
+  |
+  = help: declare it with `var` if it needs to change
+"
+    );
+}
+
+#[test]
+fn diagnostic_render_aligns_carets_after_tabs_and_wide_line_numbers() {
+    let source = SourceFile::new("main.kitty", format!("{}\t\tx + y;", "\n".repeat(11)));
+
+    assert_eq!(
+        Diagnostic::error("oops", Span::new(13, 5)).render(&source),
+        "error: oops
+  --> main.kitty:12:3
+   |
+12 | \t\tx + y;
+   | \t\t^^^^^
+"
+    );
+}
+
+#[test]
+fn diagnostic_render_multi_line_span_underlines_first_line() {
+    let source = SourceFile::new("main.kitty", "fn f() {\n}\n");
+
+    assert_eq!(
+        Diagnostic::warning("unused function", Span::new(0, 10))
+            .with_label("never called")
+            .with_help("remove it")
+            .with_help("or call it")
+            .render(&source),
+        "warning: unused function
+ --> main.kitty:1:1
+  |
+1 | fn f() {
+  | ^^^^^^^^ never called
+  |
+  = help: remove it
+  = help: or call it
+"
+    );
+}
+
+#[test]
+fn diagnostic_render_empty_span_at_end_of_input() {
+    let source = SourceFile::new("main.kitty", "1 +");
+    let error = Parser::new(source.text()).parse_expression().unwrap_err();
+
+    assert!(
+        Diagnostic::from(&error)
+            .render(&source)
+            .ends_with("1 | 1 +\n  |    ^\n")
+    );
+}
+
+#[test]
+fn diagnostic_render_colored() {
+    let source = SourceFile::new("main.kitty", "x");
+    let rendered = Renderer::colored().render(&source, &Diagnostic::error("oops", Span::new(0, 1)));
+
+    assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m\x1b[1m: oops\x1b[0m\n"));
+    assert!(rendered.contains("\x1b[1;31m^\x1b[0m"));
+    assert_eq!(
+        Renderer::plain().render(&source, &Diagnostic::error("oops", Span::new(0, 1))),
+        "error: oops\n --> main.kitty:1:1\n  |\n1 | x\n  | ^\n"
+    );
+}
@@ -0,0 +1,180 @@
+use kitty_lang_ast::sexpr;
+use kitty_lang_interpreter::{
+    formatter::{Formatter, format},
+    parser::{ParseError, Parser},
+};
+
+fn formatted(source: &str) -> String {
+    format(source).unwrap()
+}
+
+#[test]
+fn format_spaces_operators_and_indents_blocks() {
+    assert_eq!(
+        formatted("fn add(a,b){return a+b;}\nvar   x=add( 1,2 )  ;\nif(x==3){print(x<=2);}"),
+        "\
+fn add(a, b) {
+    return a + b;
+}
+var x = add(1, 2);
+if (x == 3) {
+    print(x <= 2);
+}
+"
+    );
+}
+
+#[test]
+fn format_control_flow() {
+    assert_eq!(
+        formatted(
+            "if (a) { b; } else if (c) d; else { }\n\
+             for(var i=0;i<3;i=i+1) { if(i>1)break; else continue; }\n\
+             for(;;){}\n\
+             while(not done and x>=0){x=-x;}"
+        ),
+        "\
+if (a) {
+    b;
+} else if (c) d;
+else {}
+for (var i = 0; i < 3; i = i + 1) {
+    if (i > 1) break;
+    else continue;
+}
+for (;;) {}
+while (not done and x >= 0) {
+    x = -x;
+}
+"
+    );
+}
+
+#[test]
+fn format_keeps_comments() {
+    assert_eq!(
+        formatted(
+            "// Header\n\n\n\n/// Docs.\nconst a = 1;   // trailing\n\
+             fn f() { // opening\n  // inside\n\n  return a; /* after */\n}\n"
+        ),
+        "\
+// Header
+
+/// Docs.
+const a = 1; // trailing
+fn f() { // opening
+    // inside
+
+    return a; /* after */
+}
+"
+    );
+}
+
+#[test]
+fn format_prints_statements_with_inner_comments_as_written() {
+    assert_eq!(
+        formatted("var   y = 1 + /* two */ 2;\n  x=1;"),
+        "var   y = 1 + /* two */ 2;\nx = 1;\n"
+    );
+}
+
+#[test]
+fn format_keeps_strings_and_interpolations() {
+    assert_eq!(
+        formatted("print(\"a  {x+1}  b\", r\"raw  \", \"\"\"\n  multi\n\"\"\");"),
+        "print(\"a  {x + 1}  b\", r\"raw  \", \"\"\"\n  multi\n\"\"\");\n"
+    );
+}
+
+#[test]
+fn format_wraps_long_call_arguments() {
+    let formatter = Formatter::new().with_max_width(30);
+
+    assert_eq!(
+        formatter
+            .format("print(first_argument, second(1, 2), third);\nshort(a, b);")
+            .unwrap(),
+        "\
+print(
+    first_argument,
+    second(1, 2),
+    third
+);
+short(a, b);
+"
+    );
+    assert_eq!(
+        formatter
+            .format("fn f() { return outer(inner_argument_one, inner_argument_two); }")
+            .unwrap(),
+        "\
+fn f() {
+    return outer(
+        inner_argument_one,
+        inner_argument_two
+    );
+}
+"
+    );
+}
+
+#[test]
+fn format_uses_configured_indent_width() {
+    assert_eq!(
+        Formatter::new()
+            .with_indent_width(2)
+            .format("while (x) { if (y) { z; } }")
+            .unwrap(),
+        "while (x) {\n  if (y) {\n    z;\n  }\n}\n"
+    );
+}
+
+#[test]
+fn format_is_idempotent_and_keeps_meaning() {
+    let sources = [
+        "fn fib(n){if(n<2)return n;return fib(n-1)+fib(n-2);}print(fib(10));",
+        "var s = \"{a} and {b(fn (x) { return x; })}\";",
+        "const f = fn(a) { return not a or (a and -1 < 2 * 3 / 4); };\nf(true);",
+        "for (i = 0; i < 3;) { i = i + 1; }\n\n\n{ var scoped; }",
+        "print(some_really_long_function_name_here, another_really_long_argument_value, \
+         nested(yet_another_long_argument, and_one_more_long_argument_too), 1);",
+    ];
+
+    for source in sources {
+        let once = formatted(source);
+        assert_eq!(formatted(&once), once, "formatting is not idempotent");
+        assert_eq!(
+            sexpr::program(&Parser::new(&once).parse_program().unwrap()),
+            sexpr::program(&Parser::new(source).parse_program().unwrap()),
+            "formatting changed the meaning of {source:?}"
+        );
+    }
+}
+
+#[test]
+fn format_type_annotations() {
+    assert_eq!(
+        formatted("var x :int=1;fn f(a:float,g:fn( int,int )->null)->string{return \"\";}"),
+        "\
+var x: int = 1;
+fn f(a: float, g: fn(int, int) -> null) -> string {
+    return \"\";
+}
+"
+    );
+}
+
+#[test]
+fn format_empty_source() {
+    assert_eq!(formatted(""), "");
+    assert_eq!(formatted(" \n\n"), "");
+}
+
+#[test]
+fn format_rejects_syntax_errors() {
+    assert!(matches!(
+        format("var x = ;").unwrap_err().as_slice(),
+        [ParseError::UnexpectedToken { .. }]
+    ));
+}
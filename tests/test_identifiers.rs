@@ -0,0 +1,57 @@
+use kitty_lang_ast::span::Span;
+use kitty_lang_interpreter::{
+    diagnostics::{Diagnostic, Severity},
+    identifiers::{normalize, warnings},
+};
+
+#[test]
+fn normalize_composes_to_nfc() {
+    assert_eq!(normalize("cafe\u{301}"), "café");
+    assert_eq!(normalize("café"), "café");
+    assert_eq!(normalize("cat"), "cat");
+}
+
+#[test]
+fn no_warnings_for_single_script_identifiers() {
+    assert_eq!(
+        warnings("var café = 1; var 猫 = 2; var ねこ = 猫 + café;"),
+        vec![]
+    );
+    assert_eq!(warnings("var l = 1; var I = 2; var rn = m;"), vec![]);
+}
+
+#[test]
+fn no_warnings_for_equivalent_spellings() {
+    assert_eq!(warnings("var café = 1; print(cafe\u{301});"), vec![]);
+}
+
+#[test]
+fn warn_about_mixed_script_identifier() {
+    // The first letter is a Cyrillic `р`.
+    let diagnostics = warnings("var рaypal = 1; print(рaypal);");
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::warning(
+                "identifier `рaypal` mixes characters from different scripts",
+                Span::new(4, 6)
+            )
+            .with_help("write the name using letters from a single script")
+        ]
+    );
+}
+
+#[test]
+fn warn_about_confusable_identifiers() {
+    // The second `a` is Cyrillic.
+    let diagnostics = warnings("var a = 1;\nvar а = 2;\nprint(а);");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(
+        diagnostics[0].message,
+        "identifier `а` can be confused with `a`"
+    );
+    assert_eq!(diagnostics[0].span, Span::new(15, 1));
+}
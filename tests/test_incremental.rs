@@ -0,0 +1,103 @@
+use kitty_lang_interpreter::{
+    incremental::{TextEdit, relex},
+    scanner::{Lexeme, Scanner},
+};
+
+/// Checks that relexing after replacing `range` of `source` with
+/// `replacement` gives the same lexemes as scanning the edited source.
+fn assert_relex(source: &str, range: std::ops::Range<usize>, replacement: &str) {
+    let edit = TextEdit::new(range, replacement);
+    let lexemes: Vec<Lexeme> = Scanner::new(source).collect();
+    let edited = edit.apply(source);
+
+    assert_eq!(
+        relex(&edited, &lexemes, &edit),
+        Scanner::new(&edited).collect::<Vec<_>>(),
+        "relexing {source:?} edited to {edited:?}"
+    );
+}
+
+#[test]
+fn text_edit_apply() {
+    assert_eq!(
+        TextEdit::new(4..5, "cat").apply("var x = 1;"),
+        "var cat = 1;"
+    );
+    assert_eq!(TextEdit::new(0..0, "// ").apply("x"), "// x");
+    assert_eq!(TextEdit::new(1..3, "").apply("abcd"), "ad");
+}
+
+#[test]
+fn relex_simple_edits() {
+    let source = "var x = 1;\nprint(x + 2);\n";
+
+    assert_relex(source, 4..5, "count");
+    assert_relex(source, 5..5, "y");
+    assert_relex(source, 8..9, "1.5");
+    assert_relex(source, 10..11, "");
+    assert_relex(source, 0..0, "const y = 0;\n");
+    assert_relex(source, source.len()..source.len(), "x = 3;");
+    assert_relex(source, 0..source.len(), "");
+    assert_relex("", 0..0, "var x;");
+}
+
+#[test]
+fn relex_edits_that_join_or_split_lexemes() {
+    assert_relex("a = b;", 1..2, "");
+    assert_relex("a b", 1..2, "");
+    assert_relex("ab", 1..1, " ");
+    assert_relex("x = 1;", 3..3, "=");
+    assert_relex("x == 1;", 3..4, "");
+    assert_relex("1 .5", 1..2, "");
+}
+
+#[test]
+fn relex_block_comments_and_multiline_strings() {
+    let source = "var a = 1;\n/* note */\nvar b = \"\"\"\n  text\n  \"\"\";\nvar c = 3;\n";
+
+    // Opening a comment or string swallows the rest of the source.
+    assert_relex(source, 0..0, "/*");
+    assert_relex(source, 0..0, "\"\"\"");
+    // Closing the comment early brings its contents back as code.
+    assert_relex(source, 13..13, "*/ x = ");
+    // Removing the end of the comment or string.
+    assert_relex(source, 19..21, "");
+    assert_relex(source, 41..44, "");
+    assert_relex(source, 30..30, "\n  more text");
+}
+
+#[test]
+fn relex_interpolated_strings() {
+    let source = "print(\"a {x} b {f({y})} c\");\nvar z = 1;";
+
+    assert_relex(source, 10..11, "x + 1");
+    assert_relex(source, 9..9, "\\");
+    assert_relex(source, 11..12, "");
+    assert_relex(source, 17..18, "");
+    assert_relex(source, 14..14, "\\q");
+    assert_relex(source, 6..7, "");
+    assert_relex(source, 26..26, "\"");
+}
+
+#[test]
+fn relex_non_ascii_source() {
+    let source = "var ねこ = \"🐈\";\nvar 犬 = ねこ;";
+
+    assert_relex(source, 4..10, "cat");
+    assert_relex(source, 14..18, "🐕🐕");
+    assert_relex(source, 0..0, "// 猫\n");
+    assert_relex(source, 29..29, "x");
+}
+
+#[test]
+fn relex_every_single_char_edit() {
+    let source = "fn f(a) {\n  /* c */ return \"{a}\" + r\"}\";\n}\nf(1);\n";
+
+    for (i, _) in source.char_indices() {
+        let end = source[i..].chars().next().map_or(i, |c| i + c.len_utf8());
+        for replacement in ["", "\"", "{", "}", "/*", "*/", " ", "x"] {
+            assert_relex(source, i..end, replacement);
+            assert_relex(source, i..i, replacement);
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+use kitty_lang_ast::span::Span;
+use kitty_lang_interpreter::{
+    interpreter::{Interpreter, MAX_CALL_DEPTH, RuntimeError, Value},
+    parser::Parser,
+};
+
+/// Output sink that can be inspected after the interpreter is done with it.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs `source` and returns everything it printed.
+fn run(source: &str) -> Result<String, RuntimeError> {
+    let program = Parser::new(source).parse_program().unwrap();
+    let buffer = SharedBuffer::default();
+    let mut interpreter = Interpreter::with_output(Box::new(buffer.clone()));
+
+    interpreter.run(&program)?;
+
+    let output = buffer.0.borrow().clone();
+    Ok(String::from_utf8(output).unwrap())
+}
+
+fn eval(source: &str) -> Result<Value, RuntimeError> {
+    let expr = Parser::new(source).parse_expression().unwrap();
+    Interpreter::with_output(Box::new(std::io::sink())).evaluate(&expr)
+}
+
+#[test]
+fn eval_literals() {
+    assert_eq!(eval("1"), Ok(Value::Int(1)));
+    assert_eq!(eval("2.5"), Ok(Value::Float(2.5)));
+    assert_eq!(eval("\"hi\""), Ok(Value::String("hi".into())));
+    assert_eq!(eval("true"), Ok(Value::Bool(true)));
+    assert_eq!(eval("null"), Ok(Value::Null));
+}
+
+#[test]
+fn eval_arithmetic() {
+    assert_eq!(eval("1 + 2 * 3"), Ok(Value::Int(7)));
+    assert_eq!(eval("(1 + 2) * 3"), Ok(Value::Int(9)));
+    assert_eq!(eval("7 / 2"), Ok(Value::Int(3)));
+    assert_eq!(eval("7.0 / 2"), Ok(Value::Float(3.5)));
+    assert_eq!(eval("1 - 0.5"), Ok(Value::Float(0.5)));
+    assert_eq!(eval("-(2 + 3)"), Ok(Value::Int(-5)));
+    assert_eq!(eval("5-1"), Ok(Value::Int(4)));
+    assert_eq!(eval("5 - -1"), Ok(Value::Int(6)));
+}
+
+#[test]
+fn eval_string_concatenation() {
+    assert_eq!(
+        eval("\"kitty\" + \" \" + \"lang\""),
+        Ok(Value::String("kitty lang".into()))
+    );
+}
+
+#[test]
+fn eval_interpolated_string() {
+    assert_eq!(
+        run(r#"var name = "kitty"; var count = 2;
+               print("hello {name}, you have {count + 1} items");
+               print("{1.5} {true} {null} {print} {"nested {count}"}");"#)
+        .unwrap(),
+        "hello kitty, you have 3 items\n1.5 true null <native fn print> nested 2\n"
+    );
+}
+
+#[test]
+fn eval_comparisons_and_equality() {
+    assert_eq!(eval("1 < 2"), Ok(Value::Bool(true)));
+    assert_eq!(eval("2 <= 1.5"), Ok(Value::Bool(false)));
+    assert_eq!(eval("\"b\" > \"a\""), Ok(Value::Bool(true)));
+    assert_eq!(eval("1 == 1.0"), Ok(Value::Bool(true)));
+    assert_eq!(eval("\"1\" == 1"), Ok(Value::Bool(false)));
+    assert_eq!(eval("null != null"), Ok(Value::Bool(false)));
+}
+
+#[test]
+fn eval_logical_operators_short_circuit() {
+    assert_eq!(eval("not true or false"), Ok(Value::Bool(false)));
+    assert_eq!(eval("false and undefined"), Ok(Value::Bool(false)));
+    assert_eq!(eval("true or undefined"), Ok(Value::Bool(true)));
+}
+
+#[test]
+fn eval_error_invalid_operands() {
+    //..........0123456789
+    assert_eq!(
+        eval("1 + \"a\""),
+        Err(RuntimeError::InvalidBinaryOperands {
+            op: "+",
+            left: "int",
+            right: "string",
+            span: Span::new(0, 7)
+        })
+    );
+    assert_eq!(
+        eval("not 1"),
+        Err(RuntimeError::InvalidUnaryOperand {
+            op: "not",
+            operand: "int",
+            span: Span::new(0, 5)
+        })
+    );
+    assert_eq!(
+        eval("1 and true"),
+        Err(RuntimeError::ExpectedBool {
+            found: "int",
+            span: Span::new(0, 1)
+        })
+    );
+}
+
+#[test]
+fn eval_error_division_by_zero() {
+    assert_eq!(
+        eval("1 / 0"),
+        Err(RuntimeError::DivisionByZero {
+            span: Span::new(0, 5)
+        })
+    );
+    assert_eq!(eval("1.0 / 0"), Ok(Value::Float(f64::INFINITY)));
+}
+
+#[test]
+fn eval_error_integer_overflow() {
+    assert_eq!(
+        eval("9223372036854775807 + 1"),
+        Err(RuntimeError::IntegerOverflow {
+            span: Span::new(0, 23)
+        })
+    );
+}
+
+#[test]
+fn run_print() {
+    assert_eq!(
+        run("print(1, 2.0, \"three\", true, null);").unwrap(),
+        "1 2.0 three true null\n"
+    );
+}
+
+#[test]
+fn run_variables() {
+    assert_eq!(
+        run("var a = 1; var b; print(a, b); a = a + 1; b = a * 10; print(a, b);").unwrap(),
+        "1 null\n2 20\n"
+    );
+}
+
+#[test]
+fn run_error_undefined_variable() {
+    assert_eq!(
+        run("x = 1;"),
+        Err(RuntimeError::UndefinedVariable {
+            name: "x".to_string(),
+            span: Span::new(0, 1)
+        })
+    );
+    assert_eq!(
+        run("print(y);"),
+        Err(RuntimeError::UndefinedVariable {
+            name: "y".to_string(),
+            span: Span::new(6, 1)
+        })
+    );
+}
+
+#[test]
+fn run_error_assign_to_constant() {
+    //....................01234567890123
+    assert_eq!(
+        run("const c = 1; c = 2;"),
+        Err(RuntimeError::AssignToConstant {
+            name: "c".to_string(),
+            span: Span::new(13, 1)
+        })
+    );
+}
+
+#[test]
+fn run_blocks_are_lexically_scoped() {
+    assert_eq!(
+        run("var a = 1; { var a = 2; print(a); a = 3; print(a); } print(a);").unwrap(),
+        "2\n3\n1\n"
+    );
+    assert_eq!(run("var a = 1; { a = 2; } print(a);").unwrap(), "2\n");
+}
+
+#[test]
+fn run_if_else() {
+    assert_eq!(
+        run("if (1 < 2) print(\"yes\"); else print(\"no\"); if (false) print(1);").unwrap(),
+        "yes\n"
+    );
+}
+
+#[test]
+fn run_error_non_bool_condition() {
+    assert_eq!(
+        run("if (1) {}"),
+        Err(RuntimeError::ExpectedBool {
+            found: "int",
+            span: Span::new(4, 1)
+        })
+    );
+}
+
+#[test]
+fn run_while_with_break_and_continue() {
+    assert_eq!(
+        run("var i = 0;
+             while (true) {
+                 i = i + 1;
+                 if (i == 2) continue;
+                 if (i > 4) break;
+                 print(i);
+             }")
+        .unwrap(),
+        "1\n3\n4\n"
+    );
+}
+
+#[test]
+fn run_for_loop() {
+    assert_eq!(
+        run("for (var i = 0; i < 5; i = i + 1) { if (i == 1) continue; if (i == 3) break; print(i); }")
+            .unwrap(),
+        "0\n2\n"
+    );
+}
+
+#[test]
+fn run_for_loop_variable_is_scoped_to_loop() {
+    assert_eq!(
+        run("var i = 10; for (var i = 0; i < 2; i = i + 1) {} print(i);").unwrap(),
+        "10\n"
+    );
+}
+
+#[test]
+fn run_functions() {
+    assert_eq!(
+        run("fn add(a, b) { return a + b; } print(add(1, 2), add(\"a\", \"b\"));").unwrap(),
+        "3 ab\n"
+    );
+}
+
+#[test]
+fn run_function_without_return_yields_null() {
+    assert_eq!(run("fn f() {} print(f());").unwrap(), "null\n");
+}
+
+#[test]
+fn run_return_exits_loops() {
+    assert_eq!(
+        run("fn find() {
+                 for (var i = 0; ; i = i + 1) {
+                     while (true) { if (i == 3) return i; break; }
+                 }
+             }
+             print(find());")
+        .unwrap(),
+        "3\n"
+    );
+}
+
+#[test]
+fn run_recursion() {
+    assert_eq!(
+        run("fn fib(n) { if (n < 2) return n; return fib(n - 1) + fib(n - 2); } print(fib(15));")
+            .unwrap(),
+        "610\n"
+    );
+}
+
+#[test]
+fn run_closures_capture_environment() {
+    assert_eq!(
+        run("fn counter() {
+                 var n = 0;
+                 return fn () { n = n + 1; return n; };
+             }
+             var a = counter();
+             var b = counter();
+             print(a(), a(), b());")
+        .unwrap(),
+        "1 2 1\n"
+    );
+}
+
+#[test]
+fn run_functions_are_values() {
+    assert_eq!(
+        run("fn twice(f, x) { return f(f(x)); }
+             print(twice(fn (n) { return n * 3; }, 2), twice, fn () {});")
+        .unwrap(),
+        "18 <fn twice> <fn>\n"
+    );
+}
+
+#[test]
+fn run_error_not_callable() {
+    assert_eq!(
+        run("var x = 1; x();"),
+        Err(RuntimeError::NotCallable {
+            found: "int",
+            span: Span::new(11, 3)
+        })
+    );
+}
+
+#[test]
+fn run_error_arity_mismatch() {
+    assert_eq!(
+        run("fn f(a) {} f(1, 2);"),
+        Err(RuntimeError::ArityMismatch {
+            expected: 1,
+            found: 2,
+            span: Span::new(11, 7)
+        })
+    );
+}
+
+#[test]
+fn run_error_unbounded_recursion() {
+    assert_eq!(
+        run("fn f() { return f(); } f();"),
+        Err(RuntimeError::StackOverflow {
+            limit: MAX_CALL_DEPTH,
+            span: Span::new(16, 3)
+        })
+    );
+}
+
+#[test]
+fn interpreter_keeps_globals_between_runs() {
+    let mut interpreter = Interpreter::with_output(Box::new(std::io::sink()));
+
+    let program = Parser::new("var x = 40;").parse_program().unwrap();
+    interpreter.run(&program).unwrap();
+
+    let program = Parser::new("x = x + 2;").parse_program().unwrap();
+    interpreter.run(&program).unwrap();
+
+    assert_eq!(interpreter.global("x"), Some(Value::Int(42)));
+}
+
+#[test]
+fn run_identifiers_compare_after_normalization() {
+    assert_eq!(
+        run("var café = \"decomposed\"; print(cafe\u{301}); var 猫 = 9; print(猫 + 1);").unwrap(),
+        "decomposed\n10\n"
+    );
+}
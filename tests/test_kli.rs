@@ -0,0 +1,448 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+/// Writes `source` to a file named `name` in a scratch directory and returns
+/// its path.
+fn write_script(name: &str, source: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+fn kli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_kli"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Runs kli without arguments, feeding `input` to the interactive session.
+fn kli_repl(input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_kli"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+/// Runs kli with a reader that closes stdout without reading any of it, as
+/// `head` does once it has read enough.
+fn kli_closed_stdout(args: &[&str]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_kli"))
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    drop(child.stdout.take());
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn kli_run_prints_program_output() {
+    let path = write_script(
+        "run_output.kitty",
+        "fn square(x) { return x * x; }\nprint(\"squared:\", square(7));\n",
+    );
+
+    let output = kli(&["run", path.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "squared: 49\n");
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn kli_run_passes_script_arguments() {
+    let path = write_script(
+        "run_args.kitty",
+        "for (var i = 0; i < arg_count(); i = i + 1) print(i, arg(i));\nprint(arg(5));\n",
+    );
+
+    let output = kli(&["run", path.to_str().unwrap(), "one", "--two"]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "0 one\n1 --two\nnull\n");
+}
+
+#[test]
+fn kli_run_reports_runtime_errors() {
+    let path = write_script("run_runtime_error.kitty", "print(1);\nvar x = 1 / 0;\n");
+
+    let output = kli(&["run", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "1\n");
+    assert!(stderr(&output).contains("division by zero"));
+    assert!(stderr(&output).contains("run_runtime_error.kitty:2:9"));
+}
+
+#[test]
+fn kli_run_reports_every_parse_error_without_running() {
+    let path = write_script("run_parse_error.kitty", "print(1);\nvar = 2;\nbreak;\n");
+
+    let output = kli(&["run", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("run_parse_error.kitty:2:5"));
+    assert!(stderr(&output).contains("run_parse_error.kitty:3:1"));
+}
+
+#[test]
+fn kli_run_reports_type_errors_without_running() {
+    let path = write_script("run_type_error.kitty", "print(1);\nvar x: int = \"one\";\n");
+
+    let output = kli(&["run", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("expected int but found string"));
+    assert!(stderr(&output).contains("run_type_error.kitty:2:14"));
+}
+
+#[test]
+fn kli_run_missing_file() {
+    let output = kli(&["run", "does/not/exist.kitty"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("could not read `does/not/exist.kitty`"));
+}
+
+#[test]
+fn kli_usage_errors() {
+    assert_eq!(kli(&["run"]).status.code(), Some(2));
+    assert_eq!(kli(&["frobnicate"]).status.code(), Some(2));
+}
+
+#[test]
+fn kli_repl_prints_expression_values() {
+    let output = kli_repl("1 + 2\n\"cat\"\nprint(\"side effect\")\n");
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "> 3\n> cat\n> side effect\n> \n");
+}
+
+#[test]
+fn kli_repl_keeps_state_between_lines() {
+    let output = kli_repl("var x = 20;\nfn double(n) { return n * 2; }\ndouble(x) + 2\n");
+
+    assert_eq!(stdout(&output), "> > > 42\n> \n");
+}
+
+#[test]
+fn kli_repl_continues_unbalanced_input() {
+    let output = kli_repl("fn f(a,\n b) {\n  return a - b;\n}\nf(5, 3)\n");
+
+    assert_eq!(stdout(&output), "> ... ... ... > 2\n> \n");
+}
+
+#[test]
+fn kli_repl_reports_errors_and_continues() {
+    let output = kli_repl("missing\n1 +\n;\n7\n");
+
+    assert!(output.status.success());
+    assert!(stdout(&output).ends_with("7\n> \n"));
+    assert!(stderr(&output).contains("undefined variable `missing`"));
+    assert!(stderr(&output).contains("expected an expression"));
+}
+
+#[test]
+fn kli_repl_reset_forgets_declarations() {
+    let output = kli_repl("var x = 1;\n:reset\nx\n");
+
+    assert!(stderr(&output).contains("undefined variable `x`"));
+}
+
+#[test]
+fn kli_repl_quit_stops_reading_input() {
+    let output = kli_repl(":quit\n1\n");
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "> \n");
+}
+
+#[test]
+fn kli_repl_tokens_command() {
+    let output = kli_repl(":tokens x = 1;\n");
+
+    assert_eq!(
+        stdout(&output),
+        "> 1:1 Identifier \"x\"\n1:3 Equal \"=\"\n1:5 Int \"1\"\n1:6 Semicolon \";\"\n> \n"
+    );
+}
+
+#[test]
+fn kli_tokens_text_output() {
+    let path = write_script("tokens_text.kitty", "var s = \"hi\";\n  ~\n");
+
+    let output = kli(&["tokens", path.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "1:1 Var \"var\"\n\
+         1:5 Identifier \"s\"\n\
+         1:7 Equal \"=\"\n\
+         1:9 String \"\\\"hi\\\"\"\n\
+         1:13 Semicolon \";\"\n\
+         2:3 Invalid(UnknownChars) \"~\"\n"
+    );
+}
+
+#[test]
+fn kli_tokens_json_output() {
+    let path = write_script("tokens_json.kitty", "x\n\"a\tb\" ~");
+
+    let output = kli(&["tokens", "--format", "json", path.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        r#"[
+  {"token": "Identifier", "line": 1, "column": 1, "index": 0, "length": 1, "byte_index": 0, "byte_length": 1, "text": "x"},
+  {"token": "String", "line": 2, "column": 1, "index": 2, "length": 5, "byte_index": 2, "byte_length": 5, "text": "\"a\tb\""},
+  {"token": "Invalid", "line": 2, "column": 7, "index": 8, "length": 1, "byte_index": 8, "byte_length": 1, "text": "~", "reason": "UnknownChars"}
+]
+"#
+    );
+}
+
+#[test]
+fn kli_tokens_stops_quietly_when_stdout_is_closed() {
+    let path = write_script("tokens_closed.kitty", &"var x = 1;\n".repeat(20_000));
+
+    for format in ["text", "json"] {
+        let output = kli_closed_stdout(&["tokens", "--format", format, path.to_str().unwrap()]);
+
+        assert!(output.status.success());
+        assert_eq!(stderr(&output), "");
+    }
+}
+
+#[test]
+fn kli_ast_stops_quietly_when_stdout_is_closed() {
+    let path = write_script("ast_closed.kitty", &"var x = 1 + 2;\n".repeat(20_000));
+
+    for format in ["text", "json"] {
+        let output = kli_closed_stdout(&["ast", "--format", format, path.to_str().unwrap()]);
+
+        assert!(output.status.success());
+        assert_eq!(stderr(&output), "");
+    }
+}
+
+#[test]
+fn kli_tokens_usage_errors() {
+    assert_eq!(kli(&["tokens"]).status.code(), Some(2));
+    assert_eq!(
+        kli(&["tokens", "--format=xml", "a.kitty"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(
+        kli(&["tokens", "a.kitty", "b.kitty"]).status.code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn kli_ast_text_output() {
+    let path = write_script(
+        "ast_text.kitty",
+        "var x = 1;\nwhile (x < 3) {\n  x = x + 1;\n}\n",
+    );
+
+    let output = kli(&["ast", path.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "(var x 1)\n\
+         (while (< x 3)\n\
+         \x20 (block\n\
+         \x20   (expr (= x (+ x 1)))))\n"
+    );
+}
+
+#[test]
+fn kli_ast_json_output() {
+    let path = write_script("ast_json.kitty", "-x;");
+
+    let output = kli(&["ast", "--format", "json", path.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        r#"{
+  "kind": "Program",
+  "body": [
+    {
+      "kind": "ExpressionStmt",
+      "span": {
+        "index": 0,
+        "length": 3
+      },
+      "expr": {
+        "kind": "Unary",
+        "span": {
+          "index": 0,
+          "length": 2
+        },
+        "op": "-",
+        "expr": {
+          "kind": "Variable",
+          "span": {
+            "index": 1,
+            "length": 1
+          },
+          "name": "x"
+        }
+      }
+    }
+  ]
+}
+"#
+    );
+}
+
+#[test]
+fn kli_ast_reports_parse_errors() {
+    let path = write_script("ast_parse_error.kitty", "var;");
+
+    let output = kli(&["ast", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("ast_parse_error.kitty:1:4"));
+}
+
+#[test]
+fn kli_run_renders_source_snippets_for_errors() {
+    let path = write_script("run_snippet.kitty", "var s = !true;\n");
+
+    let output = kli(&["run", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("error: `!` is not an operator\n"));
+    assert!(stderr(&output).ends_with(
+        "  |\n1 | var s = !true;\n  |         ^\n  |\n  = help: use `not` for logical negation\n\n"
+    ));
+}
+
+#[test]
+fn kli_repl_continues_unclosed_interpolation() {
+    let output = kli_repl("\"sum: {1 +\n 2}\"\n");
+
+    assert_eq!(stdout(&output), "> ... sum: 3\n> \n");
+}
+
+#[test]
+fn kli_repl_continues_multiline_strings_only() {
+    let output = kli_repl("\"\"\"\n  a\n  b\n  \"\"\"\n\"c\n");
+
+    assert_eq!(stdout(&output), "> ... ... ... a\nb\n> > \n");
+    assert!(stderr(&output).contains("unterminated string"));
+}
+
+#[test]
+fn kli_ast_json_includes_doc_comments() {
+    let path = write_script(
+        "ast_doc.kitty",
+        "/// Counts things.\nvar count;\nfn f() {}\n",
+    );
+
+    let output = kli(&["ast", "--format=json", path.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("\"doc\": \"Counts things.\""));
+    assert!(stdout(&output).contains("\"doc\": null"));
+}
+
+#[test]
+fn kli_run_warns_about_confusable_identifiers() {
+    let path = write_script(
+        "run_confusable.kitty",
+        "var a = 1;\nvar а = 2;\nprint(a + а);\n",
+    );
+
+    let output = kli(&["run", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "3\n");
+    assert!(stderr(&output).contains("warning: identifier `а` can be confused with `a`"));
+    assert!(stderr(&output).contains("run_confusable.kitty:2:5"));
+}
+
+#[test]
+fn kli_fmt_rewrites_files() {
+    let messy = write_script("fmt_messy.kitty", "var x=1;\nif(x==1){print(x);}\n");
+    let tidy = write_script("fmt_tidy.kitty", "var y = 2;\n");
+
+    let output = kli(&["fmt", messy.to_str().unwrap(), tidy.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "");
+    assert_eq!(
+        std::fs::read_to_string(&messy).unwrap(),
+        "var x = 1;\nif (x == 1) {\n    print(x);\n}\n"
+    );
+    assert_eq!(std::fs::read_to_string(&tidy).unwrap(), "var y = 2;\n");
+}
+
+#[test]
+fn kli_fmt_check_lists_unformatted_files() {
+    let messy = write_script("fmt_check_messy.kitty", "print( 1 );");
+    let tidy = write_script("fmt_check_tidy.kitty", "print(1);\n");
+
+    let output = kli(&["fmt", "--check", tidy.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+
+    let output = kli(&[
+        "fmt",
+        "--check",
+        messy.to_str().unwrap(),
+        tidy.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        format!("{} is not formatted\n", messy.to_str().unwrap())
+    );
+    assert_eq!(std::fs::read_to_string(&messy).unwrap(), "print( 1 );");
+}
+
+#[test]
+fn kli_fmt_reports_parse_errors() {
+    let path = write_script("fmt_parse_error.kitty", "var x = ;\n");
+
+    let output = kli(&["fmt", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("fmt_parse_error.kitty:1:9"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "var x = ;\n");
+    assert_eq!(kli(&["fmt"]).status.code(), Some(2));
+    assert_eq!(kli(&["fmt", "--diff", "x.kitty"]).status.code(), Some(2));
+}
@@ -0,0 +1,97 @@
+use kitty_lang_interpreter::literals::{
+    InvalidEscape, NumberError, dedent, parse_float, parse_int, unescape_string,
+};
+
+#[test]
+fn unescape_plain_text() {
+    assert_eq!(unescape_string(""), Ok(String::new()));
+    assert_eq!(unescape_string("héllo 日本"), Ok("héllo 日本".to_string()));
+}
+
+#[test]
+fn unescape_simple_escapes() {
+    assert_eq!(
+        unescape_string(r#"\"a\\b\nc\rd\te\0"#),
+        Ok("\"a\\b\nc\rd\te\0".to_string())
+    );
+}
+
+#[test]
+fn unescape_unicode_escapes() {
+    assert_eq!(unescape_string(r"\u{41}\u{e9}"), Ok("Aé".to_string()));
+    assert_eq!(unescape_string(r"\u{1F431}!"), Ok("🐱!".to_string()));
+    assert_eq!(unescape_string(r"\u{00000a}"), Ok("\n".to_string()));
+}
+
+#[test]
+fn unescape_invalid_escapes() {
+    let invalid = |text: &str, byte_index| {
+        Err(InvalidEscape {
+            text: text.to_string(),
+            byte_index,
+        })
+    };
+
+    assert_eq!(unescape_string(r"ab\q"), invalid(r"\q", 2));
+    assert_eq!(unescape_string(r"é\é"), invalid(r"\é", 2));
+    assert_eq!(unescape_string(r"\"), invalid(r"\", 0));
+    assert_eq!(unescape_string(r"\u41"), invalid(r"\u", 0));
+    assert_eq!(unescape_string(r"\u{}"), invalid(r"\u{}", 0));
+    assert_eq!(unescape_string(r"\u{41"), invalid(r"\u{41", 0));
+    assert_eq!(unescape_string(r"\u{1234567}"), invalid(r"\u{1234567}", 0));
+    assert_eq!(unescape_string(r"\u{D800}"), invalid(r"\u{D800}", 0));
+    assert_eq!(unescape_string(r"\u{110000}"), invalid(r"\u{110000}", 0));
+}
+
+#[test]
+fn dedent_removes_delimiter_lines_and_common_indentation() {
+    assert_eq!(dedent("\n    hello\n      world\n    "), "hello\n  world");
+    assert_eq!(dedent("\r\n\ta\r\n\r\n\t\tb\r\n\t"), "a\n\n\tb");
+    assert_eq!(dedent("\n  a\n\n  b\n  "), "a\n\nb");
+}
+
+#[test]
+fn dedent_closing_delimiter_sets_indentation() {
+    assert_eq!(dedent("\n    a\n      b\n  "), "  a\n    b");
+}
+
+#[test]
+fn dedent_single_line() {
+    assert_eq!(dedent("abc"), "abc");
+    assert_eq!(dedent(" abc "), " abc ");
+    assert_eq!(dedent(""), "");
+}
+
+#[test]
+fn parse_int_literals() {
+    assert_eq!(parse_int("0"), Ok(0));
+    assert_eq!(parse_int("1_000"), Ok(1000));
+    assert_eq!(parse_int("0x1F"), Ok(31));
+    assert_eq!(parse_int("0o17"), Ok(15));
+    assert_eq!(parse_int("0b1010"), Ok(10));
+    assert_eq!(parse_int("-0x10"), Ok(-16));
+    assert_eq!(parse_int("-9223372036854775808"), Ok(i64::MIN));
+    assert_eq!(
+        parse_int("9223372036854775808"),
+        Err(NumberError::OutOfRange)
+    );
+    assert_eq!(
+        parse_int("-0x8000000000000001"),
+        Err(NumberError::OutOfRange)
+    );
+    assert_eq!(parse_int("+1"), Err(NumberError::Invalid));
+    assert_eq!(parse_int("0x"), Err(NumberError::Invalid));
+    assert_eq!(parse_int("0b2"), Err(NumberError::Invalid));
+}
+
+#[test]
+fn parse_float_literals() {
+    assert_eq!(parse_float("1.5"), Ok(1.5));
+    assert_eq!(parse_float("2."), Ok(2.0));
+    assert_eq!(parse_float("1_000.000_1"), Ok(1000.0001));
+    assert_eq!(parse_float("1.5e-3"), Ok(0.0015));
+    assert_eq!(parse_float("-2E2"), Ok(-200.0));
+    assert_eq!(parse_float("1e309"), Err(NumberError::OutOfRange));
+    assert_eq!(parse_float(".5"), Err(NumberError::Invalid));
+    assert_eq!(parse_float("inf"), Err(NumberError::Invalid));
+}
@@ -0,0 +1,658 @@
+use kitty_lang_ast::{
+    expr::{Expr, ExprKind, Literal},
+    sexpr,
+    span::Span,
+    stmt::StmtKind,
+};
+use kitty_lang_interpreter::{
+    parser::{ParseError, Parser},
+    tokens::{InvalidTokenReason, Token},
+};
+
+fn parse(source: &str) -> String {
+    sexpr::expr(&Parser::new(source).parse_expression().unwrap())
+}
+
+fn parse_program(source: &str) -> Vec<String> {
+    Parser::new(source)
+        .parse_program()
+        .unwrap()
+        .iter()
+        .map(sexpr::stmt)
+        .collect()
+}
+
+#[test]
+fn parse_literals() {
+    assert_eq!(parse("12"), "12");
+    assert_eq!(parse("1.5"), "1.5");
+    assert_eq!(parse("0xff_ff"), "65535");
+    assert_eq!(parse("1.5e3"), "1500.0");
+    assert_eq!(parse("\"hi there\""), "\"hi there\"");
+    assert_eq!(parse("true"), "true");
+    assert_eq!(parse("false"), "false");
+    assert_eq!(parse("null"), "null");
+    assert_eq!(parse("foo"), "foo");
+}
+
+#[test]
+fn parse_literal_spans() {
+    //...................................0123456789
+    let expr = Parser::new("  \"hello\"").parse_expression().unwrap();
+    assert_eq!(
+        expr,
+        Expr {
+            kind: ExprKind::Literal(Literal::String("hello".to_string())),
+            span: Span {
+                index: 2,
+                length: 7
+            }
+        }
+    );
+}
+
+#[test]
+fn parse_string_literal_decodes_escapes() {
+    let expr = Parser::new(r#""tab\there \"\u{1F431}\"""#)
+        .parse_expression()
+        .unwrap();
+    assert_eq!(
+        expr.kind,
+        ExprKind::Literal(Literal::String("tab\there \"🐱\"".to_string()))
+    );
+}
+
+#[test]
+fn parse_interpolated_string() {
+    assert_eq!(parse(r#""hi {name}!""#), r#"(str "hi " name "!")"#);
+    assert_eq!(parse(r#""{a}{b + 1}""#), "(str a (+ b 1))");
+    assert_eq!(
+        parse(r#""outer {"inner {x}"} \{y\}""#),
+        r#"(str "outer " (str "inner " x) " {y}")"#
+    );
+}
+
+#[test]
+fn parse_interpolated_string_spans() {
+    //.....................................012345678
+    let expr = Parser::new(r#""a{x}b""#).parse_expression().unwrap();
+    assert_eq!(expr.span, Span::new(0, 7));
+
+    let ExprKind::Interpolation(parts) = expr.kind else {
+        panic!("expected an interpolated string");
+    };
+    assert_eq!(
+        parts.iter().map(|p| p.span).collect::<Vec<_>>(),
+        vec![Span::new(0, 3), Span::new(3, 1), Span::new(4, 3)]
+    );
+}
+
+#[test]
+fn parse_error_unclosed_interpolation() {
+    assert_eq!(
+        Parser::new(r#""a {x y}""#).parse_expression(),
+        Err(ParseError::UnexpectedToken {
+            expected: "'}' after interpolated expression",
+            found: Token::Identifier,
+            span: Span::new(6, 1)
+        })
+    );
+}
+
+#[test]
+fn parse_unary() {
+    assert_eq!(parse("not true"), "(not true)");
+    assert_eq!(parse("- x"), "(- x)");
+    assert_eq!(parse("not not x"), "(not (not x))");
+}
+
+#[test]
+fn parse_negative_literals() {
+    assert_eq!(parse("a-1"), "(- a 1)");
+    assert_eq!(parse("a - -1"), "(- a -1)");
+    assert_eq!(parse("-(1)"), "(- (group 1))");
+    assert_eq!(parse("- 2.5"), "-2.5");
+    assert_eq!(parse("--1"), "(- -1)");
+    assert_eq!(parse("-1()"), "(- (call 1))");
+    assert_eq!(parse("-0x10 * 2"), "(* -16 2)");
+    assert_eq!(parse("-9223372036854775808"), "-9223372036854775808");
+}
+
+#[test]
+fn parse_negative_literal_spans() {
+    //.....................................0123456
+    let expr = Parser::new("a - -1").parse_expression().unwrap();
+    let ExprKind::Binary { right, .. } = expr.kind else {
+        panic!("expected a binary expression");
+    };
+    assert_eq!(right.kind, ExprKind::Literal(Literal::Int(-1)));
+    assert_eq!(right.span, Span::new(4, 2));
+}
+
+#[test]
+fn parse_factor_binds_tighter_than_term() {
+    assert_eq!(parse("1 + 2 * 3"), "(+ 1 (* 2 3))");
+    assert_eq!(parse("1 * 2 + 3"), "(+ (* 1 2) 3)");
+    assert_eq!(parse("a / b - c"), "(- (/ a b) c)");
+}
+
+#[test]
+fn parse_binary_ops_are_left_associative() {
+    assert_eq!(parse("a - b - c"), "(- (- a b) c)");
+    assert_eq!(parse("a / b / c"), "(/ (/ a b) c)");
+    assert_eq!(parse("a == b != c"), "(!= (== a b) c)");
+}
+
+#[test]
+fn parse_comparison_and_equality_precedence() {
+    assert_eq!(parse("a < b == c >= d"), "(== (< a b) (>= c d))");
+    assert_eq!(parse("a + 1 <= b * 2"), "(<= (+ a 1) (* b 2))");
+}
+
+#[test]
+fn parse_logical_precedence() {
+    assert_eq!(parse("a or b and c"), "(or a (and b c))");
+    assert_eq!(parse("a and b or c"), "(or (and a b) c)");
+    assert_eq!(parse("not a == b and c"), "(and (== (not a) b) c)");
+}
+
+#[test]
+fn parse_grouping_overrides_precedence() {
+    assert_eq!(parse("(1 + 2) * 3"), "(* (group (+ 1 2)) 3)");
+    assert_eq!(parse("((x))"), "(group (group x))");
+}
+
+#[test]
+fn parse_binary_span_covers_operands() {
+    //...................................0123456789
+    let expr = Parser::new("(a + b) * c").parse_expression().unwrap();
+    assert_eq!(expr.span, Span::new(0, 11));
+}
+
+#[test]
+fn parse_ignores_comments() {
+    assert_eq!(parse("1 + // one\n 2"), "(+ 1 2)");
+}
+
+#[test]
+fn parse_error_unclosed_group() {
+    assert_eq!(
+        Parser::new("(1 + 2").parse_expression(),
+        Err(ParseError::UnexpectedEof {
+            expected: "')' after expression",
+            span: Span::new(6, 0)
+        })
+    );
+}
+
+#[test]
+fn parse_error_missing_operand() {
+    assert_eq!(
+        Parser::new("1 + *").parse_expression(),
+        Err(ParseError::UnexpectedToken {
+            expected: "an expression",
+            found: Token::Star,
+            span: Span::new(4, 1)
+        })
+    );
+    assert_eq!(
+        Parser::new("1 + *")
+            .parse_expression()
+            .unwrap_err()
+            .to_string(),
+        "expected an expression but found `*`"
+    );
+}
+
+#[test]
+fn parse_error_trailing_tokens() {
+    assert_eq!(
+        Parser::new("1 2").parse_expression(),
+        Err(ParseError::UnexpectedToken {
+            expected: "end of input",
+            found: Token::Int,
+            span: Span::new(2, 1)
+        })
+    );
+}
+
+#[test]
+fn parse_error_invalid_token() {
+    assert_eq!(
+        Parser::new("1 + ~").parse_expression(),
+        Err(ParseError::InvalidToken {
+            reason: InvalidTokenReason::UnknownChars,
+            text: "~".to_string(),
+            span: Span::new(4, 1)
+        })
+    );
+}
+
+#[test]
+fn parse_reports_one_error_per_run_of_invalid_chars() {
+    let errors = Parser::new("var a = 2pxyz;\nvar b = ~~~;")
+        .parse_program()
+        .unwrap_err();
+
+    assert_eq!(
+        errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+        vec![
+            "invalid characters in number `2pxyz`",
+            "unknown characters `~~~`"
+        ]
+    );
+}
+
+#[test]
+fn parse_error_int_out_of_range() {
+    assert_eq!(
+        Parser::new("99999999999999999999").parse_expression(),
+        Err(ParseError::NumberOutOfRange {
+            token: Token::Int,
+            span: Span::new(0, 20)
+        })
+    );
+    assert_eq!(
+        Parser::new("-9223372036854775809").parse_expression(),
+        Err(ParseError::NumberOutOfRange {
+            token: Token::Int,
+            span: Span::new(0, 20)
+        })
+    );
+}
+
+#[test]
+fn parse_error_float_out_of_range() {
+    assert_eq!(
+        Parser::new("-1e999").parse_expression(),
+        Err(ParseError::NumberOutOfRange {
+            token: Token::Float,
+            span: Span::new(0, 6)
+        })
+    );
+}
+
+#[test]
+fn parse_assignment_is_right_associative() {
+    assert_eq!(parse("a = b = 1 + 2"), "(= a (= b (+ 1 2)))");
+}
+
+#[test]
+fn parse_error_invalid_assignment_target() {
+    assert_eq!(
+        Parser::new("1 + a = 2").parse_expression(),
+        Err(ParseError::InvalidAssignmentTarget {
+            span: Span::new(0, 5)
+        })
+    );
+}
+
+#[test]
+fn parse_empty_program() {
+    assert_eq!(parse_program("  // nothing here"), Vec::<String>::new());
+}
+
+#[test]
+fn parse_var_and_const_declarations() {
+    assert_eq!(
+        parse_program("var x; var y = 1 + 2; const z = y;"),
+        vec!["(var x _)", "(var y (+ 1 2))", "(const z y)"]
+    );
+}
+
+#[test]
+fn parse_type_annotations() {
+    assert_eq!(
+        parse_program(
+            "var x: int = 1; const f: fn(int, float) -> string = g; fn h(a: float, b) -> string {}"
+        ),
+        vec![
+            "(var x:int 1)",
+            "(const f:fn(int, float) -> string g)",
+            "(fn h (a:float b) -> string)"
+        ]
+    );
+}
+
+#[test]
+fn parse_type_annotation_spans() {
+    //............................0123456789012345678901234
+    let stmts = Parser::new("var f: fn(int) -> null;")
+        .parse_program()
+        .unwrap();
+    let StmtKind::Var(decl) = &stmts[0].kind else {
+        panic!("expected a var declaration");
+    };
+    assert_eq!(decl.ty.as_ref().unwrap().span, Span::new(7, 15));
+}
+
+#[test]
+fn parse_error_missing_type() {
+    assert_eq!(
+        Parser::new("var x: = 1;").parse_program(),
+        Err(vec![ParseError::UnexpectedToken {
+            expected: "a type",
+            found: Token::Equal,
+            span: Span::new(7, 1)
+        }])
+    );
+}
+
+#[test]
+fn parse_declaration_span_includes_semicolon() {
+    //............................0123456789012
+    let stmts = Parser::new("  var x = 1;").parse_program().unwrap();
+    assert_eq!(stmts[0].span, Span::new(2, 10));
+}
+
+#[test]
+fn parse_error_const_requires_initializer() {
+    assert_eq!(
+        Parser::new("const x;").parse_program(),
+        Err(vec![ParseError::UnexpectedToken {
+            expected: "'=' after constant name",
+            found: Token::Semicolon,
+            span: Span::new(7, 1)
+        }])
+    );
+}
+
+#[test]
+fn parse_expression_statements() {
+    assert_eq!(
+        parse_program("x = 2; x + 1;"),
+        vec!["(expr (= x 2))", "(expr (+ x 1))"]
+    );
+}
+
+#[test]
+fn parse_blocks() {
+    assert_eq!(
+        parse_program("{ var a = 1; { a; } } {}"),
+        vec!["(block (var a 1) (block (expr a)))", "(block)"]
+    );
+}
+
+#[test]
+fn parse_if_else() {
+    assert_eq!(
+        parse_program("if (a) b; if (a) { b; } else c;"),
+        vec!["(if a (expr b))", "(if a (block (expr b)) (expr c))"]
+    );
+}
+
+#[test]
+fn parse_dangling_else_binds_to_nearest_if() {
+    assert_eq!(
+        parse_program("if (a) if (b) c; else d;"),
+        vec!["(if a (if b (expr c) (expr d)))"]
+    );
+}
+
+#[test]
+fn parse_while_loop() {
+    assert_eq!(
+        parse_program("while (x < 10) { x = x + 1; }"),
+        vec!["(while (< x 10) (block (expr (= x (+ x 1)))))"]
+    );
+}
+
+#[test]
+fn parse_for_loops() {
+    assert_eq!(
+        parse_program("for (var i = 0; i < 3; i = i + 1) i; for (;;) break;"),
+        vec![
+            "(for (var i 0) (< i 3) (= i (+ i 1)) (expr i))",
+            "(for _ _ _ (break))"
+        ]
+    );
+}
+
+#[test]
+fn parse_break_and_continue_in_loops() {
+    assert_eq!(
+        parse_program("while (true) { if (a) break; continue; }"),
+        vec!["(while true (block (if a (break)) (continue)))"]
+    );
+}
+
+#[test]
+fn parse_return() {
+    assert_eq!(
+        parse_program("fn f() { return; return 1 + x; }"),
+        vec!["(fn f () (return _) (return (+ 1 x)))"]
+    );
+}
+
+#[test]
+fn parse_error_return_outside_function() {
+    assert_eq!(
+        Parser::new("return 1;").parse_program(),
+        Err(vec![ParseError::ReturnOutsideFunction {
+            span: Span::new(0, 6)
+        }])
+    );
+}
+
+#[test]
+fn parse_error_break_outside_loop() {
+    assert_eq!(
+        Parser::new("break; while (a) {} continue;").parse_program(),
+        Err(vec![
+            ParseError::BreakOutsideLoop {
+                span: Span::new(0, 5)
+            },
+            ParseError::ContinueOutsideLoop {
+                span: Span::new(20, 8)
+            }
+        ])
+    );
+}
+
+#[test]
+fn parse_error_missing_semicolon() {
+    assert_eq!(
+        Parser::new("x = 1").parse_program(),
+        Err(vec![ParseError::UnexpectedEof {
+            expected: "';' after expression",
+            span: Span::new(5, 0)
+        }])
+    );
+}
+
+#[test]
+fn parse_reports_errors_from_multiple_statements() {
+    let errors = Parser::new("var = 1; var ok = 2; 1 +; if (x) {")
+        .parse_program()
+        .unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![
+            ParseError::UnexpectedToken {
+                expected: "a variable name",
+                found: Token::Equal,
+                span: Span::new(4, 1)
+            },
+            ParseError::UnexpectedToken {
+                expected: "an expression",
+                found: Token::Semicolon,
+                span: Span::new(24, 1)
+            },
+            ParseError::UnexpectedEof {
+                expected: "'}' after block",
+                span: Span::new(34, 0)
+            },
+        ]
+    );
+}
+
+#[test]
+fn parse_function_declarations() {
+    assert_eq!(
+        parse_program("fn none() {} fn one(a) { a; } fn two(a, b) { return a + b; }"),
+        vec![
+            "(fn none ())",
+            "(fn one (a) (expr a))",
+            "(fn two (a b) (return (+ a b)))"
+        ]
+    );
+}
+
+#[test]
+fn parse_function_declaration_span() {
+    //............................012345678901234
+    let stmts = Parser::new("fn f(a) { a; }").parse_program().unwrap();
+    assert_eq!(stmts[0].span, Span::new(0, 14));
+}
+
+#[test]
+fn parse_calls() {
+    assert_eq!(parse("f()"), "(call f)");
+    assert_eq!(parse("f(1, a + b)"), "(call f 1 (+ a b))");
+    assert_eq!(parse("f(1)(2)"), "(call (call f 1) 2)");
+    assert_eq!(parse("-f(x) * 2"), "(* (- (call f x)) 2)");
+}
+
+#[test]
+fn parse_anonymous_functions() {
+    assert_eq!(
+        parse("fn (x) { return x * 2; }"),
+        "(fn _ (x) (return (* x 2)))"
+    );
+    assert_eq!(
+        parse_program("var add = fn (a, b) { return a + b; }; fn () {}();"),
+        vec![
+            "(var add (fn _ (a b) (return (+ a b))))",
+            "(expr (call (fn _ ())))"
+        ]
+    );
+}
+
+#[test]
+fn parse_nested_functions_return_closures() {
+    assert_eq!(
+        parse_program("fn counter() { var n = 0; return fn () { n = n + 1; return n; }; }"),
+        vec!["(fn counter () (var n 0) (return (fn _ () (expr (= n (+ n 1))) (return n))))"]
+    );
+}
+
+#[test]
+fn parse_error_break_inside_function_inside_loop() {
+    assert_eq!(
+        Parser::new("while (true) { fn f() { break; } }")
+            .parse_program()
+            .unwrap_err()[0],
+        ParseError::BreakOutsideLoop {
+            span: Span::new(24, 5)
+        }
+    );
+}
+
+#[test]
+fn parse_error_unclosed_argument_list() {
+    assert_eq!(
+        Parser::new("f(1, 2;").parse_program(),
+        Err(vec![ParseError::UnexpectedToken {
+            expected: "')' after arguments",
+            found: Token::Semicolon,
+            span: Span::new(6, 1)
+        }])
+    );
+}
+
+#[test]
+fn sexpr_program_indents_nested_statements() {
+    let program = Parser::new(
+        "var x = 1; fn f(a) { while (a < x) { a = a + 1; } return a; } if (x) y; else { z; }",
+    )
+    .parse_program()
+    .unwrap();
+
+    assert_eq!(
+        sexpr::program(&program),
+        "(var x 1)
+(fn f (a)
+  (while (< a x)
+    (block
+      (expr (= a (+ a 1)))))
+  (return a))
+(if x
+  (expr y)
+  (block
+    (expr z)))
+"
+    );
+}
+
+#[test]
+fn parse_ignores_block_comments() {
+    assert_eq!(
+        parse_program("var /* name: */ x = 1 /* + /* nested */ 2 */;"),
+        vec!["(var x 1)"]
+    );
+}
+
+#[test]
+fn parse_attaches_doc_comments_to_declarations() {
+    let program = Parser::new(
+        "/// The answer.
+         ///
+         ///   Indented line.
+         const answer = 42;
+
+         // Not documentation.
+         /// Adds `a` and `b`.
+         fn add(a, b) {
+             /// Not a declaration.
+             return a + b;
+         }
+
+         var plain = fn () {};",
+    )
+    .parse_program()
+    .unwrap();
+
+    let StmtKind::Var(answer) = &program[0].kind else {
+        panic!("expected a const declaration");
+    };
+    assert_eq!(
+        answer.doc.as_deref(),
+        Some("The answer.\n\n  Indented line.")
+    );
+
+    let StmtKind::Fn(add) = &program[1].kind else {
+        panic!("expected a function declaration");
+    };
+    assert_eq!(add.doc.as_deref(), Some("Adds `a` and `b`."));
+
+    let StmtKind::Var(plain) = &program[2].kind else {
+        panic!("expected a var declaration");
+    };
+    assert_eq!(plain.doc, None);
+}
+
+#[test]
+fn parse_error_keyword_as_name() {
+    let errors = Parser::new("var while = 1;\nfn f(if) {}").parse_program();
+
+    assert_eq!(
+        errors,
+        Err(vec![
+            ParseError::KeywordAsIdentifier {
+                expected: "a variable name",
+                keyword: "while",
+                span: Span::new(4, 5)
+            },
+            ParseError::KeywordAsIdentifier {
+                expected: "a parameter name",
+                keyword: "if",
+                span: Span::new(20, 2)
+            },
+        ])
+    );
+    assert_eq!(
+        errors.unwrap_err()[0].to_string(),
+        "expected a variable name but found keyword `while`"
+    );
+}
@@ -1,6 +1,7 @@
 use kitty_lang_interpreter::{
+    literals::NumberError,
     scanner::{Lexeme, Scanner},
-    tokens::{InvalidTokenReason, Token},
+    tokens::{InvalidTokenReason, KEYWORDS, Token},
 };
 
 #[test]
@@ -15,7 +16,9 @@ fn scan_invalid_token() {
         vec![Lexeme {
             token: Token::Invalid(InvalidTokenReason::UnknownChars),
             index: 0,
-            length: 1
+            length: 1,
+            byte_index: 0,
+            byte_length: 1
         }]
     );
 }
@@ -28,62 +31,86 @@ fn scan_single_char_lexemes() {
             Lexeme {
                 token: Token::LeftParen,
                 index: 0,
-                length: 1
+                length: 1,
+                byte_index: 0,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::RightParen,
                 index: 1,
-                length: 1
+                length: 1,
+                byte_index: 1,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::RightBrace,
                 index: 2,
-                length: 1
+                length: 1,
+                byte_index: 2,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::LeftBrace,
                 index: 3,
-                length: 1
+                length: 1,
+                byte_index: 3,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::LeftBracket,
                 index: 4,
-                length: 1
+                length: 1,
+                byte_index: 4,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::RightBracket,
                 index: 5,
-                length: 1
+                length: 1,
+                byte_index: 5,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Minus,
                 index: 6,
-                length: 1
+                length: 1,
+                byte_index: 6,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Slash,
                 index: 7,
-                length: 1
+                length: 1,
+                byte_index: 7,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Semicolon,
                 index: 8,
-                length: 1
+                length: 1,
+                byte_index: 8,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Comma,
                 index: 9,
-                length: 1
+                length: 1,
+                byte_index: 9,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Plus,
                 index: 10,
-                length: 1
+                length: 1,
+                byte_index: 10,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Star,
                 index: 11,
-                length: 1
+                length: 1,
+                byte_index: 11,
+                byte_length: 1
             }
         ]
     );
@@ -97,42 +124,66 @@ fn scanner_disambiguates_two_char_lexemes() {
             Lexeme {
                 token: Token::EqualEqual,
                 index: 0,
-                length: 2
+                length: 2,
+                byte_index: 0,
+                byte_length: 2
             },
             Lexeme {
                 token: Token::Equal,
                 index: 2,
-                length: 1
+                length: 1,
+                byte_index: 2,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Less,
                 index: 3,
-                length: 1
+                length: 1,
+                byte_index: 3,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::LessEqual,
                 index: 4,
-                length: 2
+                length: 2,
+                byte_index: 4,
+                byte_length: 2
             },
             Lexeme {
                 token: Token::Greater,
                 index: 6,
-                length: 1
+                length: 1,
+                byte_index: 6,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::GreaterEqual,
                 index: 7,
-                length: 2
+                length: 2,
+                byte_index: 7,
+                byte_length: 2
             },
             Lexeme {
                 token: Token::BangEqual,
                 index: 9,
-                length: 2
+                length: 2,
+                byte_index: 9,
+                byte_length: 2
             }
         ]
     )
 }
 
+#[test]
+fn scanner_reads_type_annotation_punctuation() {
+    assert_eq!(
+        Scanner::new("a: ->-")
+            .map(|lexeme| lexeme.token)
+            .collect::<Vec<_>>(),
+        vec![Token::Identifier, Token::Colon, Token::Arrow, Token::Minus]
+    );
+}
+
 #[test]
 fn scanner_skips_whitespace() {
     //................01234 567890 123456
@@ -142,27 +193,37 @@ fn scanner_skips_whitespace() {
             Lexeme {
                 token: Token::EqualEqual,
                 index: 2,
-                length: 2
+                length: 2,
+                byte_index: 2,
+                byte_length: 2
             },
             Lexeme {
                 token: Token::Equal,
                 index: 6,
-                length: 1
+                length: 1,
+                byte_index: 6,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Equal,
                 index: 8,
-                length: 1
+                length: 1,
+                byte_index: 8,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Less,
                 index: 12,
-                length: 1
+                length: 1,
+                byte_index: 12,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Greater,
                 index: 13,
-                length: 1
+                length: 1,
+                byte_index: 13,
+                byte_length: 1
             },
         ]
     )
@@ -177,12 +238,16 @@ fn scanner_line_comment() {
             Lexeme {
                 token: Token::Plus,
                 index: 1,
-                length: 1
+                length: 1,
+                byte_index: 1,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Comment,
                 index: 2,
-                length: 8
+                length: 8,
+                byte_index: 2,
+                byte_length: 8
             },
         ]
     )
@@ -190,24 +255,30 @@ fn scanner_line_comment() {
 
 #[test]
 fn scanner_read_strings() {
-    //................ 012 3 4567 8901 2345 67890 1
+    //................ 012 3 4 5 6789 0123 4 5 6789 01 2345 6
     assert_eq!(
-        Scanner::new("\"hi\"\"one\ntwo\"   \"okay\"").collect::<Vec<_>>(),
+        Scanner::new("\"hi\"\"\"\"one\ntwo\"\"\"   \"okay\"").collect::<Vec<_>>(),
         vec![
             Lexeme {
                 token: Token::String,
                 index: 0,
-                length: 4
+                length: 4,
+                byte_index: 0,
+                byte_length: 4
             },
             Lexeme {
-                token: Token::String,
+                token: Token::MultilineString,
                 index: 4,
-                length: 9
+                length: 13,
+                byte_index: 4,
+                byte_length: 13
             },
             Lexeme {
                 token: Token::String,
-                index: 16,
-                length: 6
+                index: 20,
+                length: 6,
+                byte_index: 20,
+                byte_length: 6
             },
         ]
     )
@@ -221,7 +292,9 @@ fn scanner_error_if_terminating_quote_missing() {
         vec![Lexeme {
             token: Token::Invalid(InvalidTokenReason::UnterminatedString),
             index: 0,
-            length: 6
+            length: 6,
+            byte_index: 0,
+            byte_length: 6
         },]
     )
 }
@@ -234,7 +307,9 @@ fn scaner_read_number() {
         vec![Lexeme {
             token: Token::Invalid(InvalidTokenReason::UnterminatedString),
             index: 0,
-            length: 6
+            length: 6,
+            byte_index: 0,
+            byte_length: 6
         },]
     )
 }
@@ -248,22 +323,44 @@ fn scanner_read_numbers() {
             Lexeme {
                 token: Token::Float,
                 index: 0,
-                length: 3
+                length: 3,
+                byte_index: 0,
+                byte_length: 3
             },
             Lexeme {
-                token: Token::Float,
+                token: Token::Minus,
                 index: 4,
-                length: 4
+                length: 1,
+                byte_index: 4,
+                byte_length: 1
+            },
+            Lexeme {
+                token: Token::Float,
+                index: 5,
+                length: 3,
+                byte_index: 5,
+                byte_length: 3
             },
             Lexeme {
                 token: Token::Int,
                 index: 11,
-                length: 2
+                length: 2,
+                byte_index: 11,
+                byte_length: 2
             },
             Lexeme {
-                token: Token::Int,
+                token: Token::Minus,
                 index: 16,
-                length: 2
+                length: 1,
+                byte_index: 16,
+                byte_length: 1
+            },
+            Lexeme {
+                token: Token::Int,
+                index: 17,
+                length: 1,
+                byte_index: 17,
+                byte_length: 1
             },
         ]
     )
@@ -277,7 +374,9 @@ fn scanner_read_bad_int() {
         vec![Lexeme {
             token: Token::Invalid(InvalidTokenReason::UnknownNumberChars),
             index: 0,
-            length: 2
+            length: 2,
+            byte_index: 0,
+            byte_length: 2
         },]
     )
 }
@@ -290,7 +389,9 @@ fn scanner_read_bad_float() {
         vec![Lexeme {
             token: Token::Invalid(InvalidTokenReason::UnknownNumberChars),
             index: 0,
-            length: 4
+            length: 4,
+            byte_index: 0,
+            byte_length: 4
         },]
     )
 }
@@ -304,22 +405,30 @@ fn scaner_read_identifiers() {
             Lexeme {
                 token: Token::Identifier,
                 index: 0,
-                length: 1
+                length: 1,
+                byte_index: 0,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Plus,
                 index: 1,
-                length: 1
+                length: 1,
+                byte_index: 1,
+                byte_length: 1
             },
             Lexeme {
                 token: Token::Identifier,
                 index: 2,
-                length: 2
+                length: 2,
+                byte_index: 2,
+                byte_length: 2
             },
             Lexeme {
                 token: Token::Identifier,
                 index: 6,
-                length: 3
+                length: 3,
+                byte_index: 6,
+                byte_length: 3
             },
         ]
     )
@@ -334,27 +443,37 @@ fn scanner_read_keywords_1() {
             Lexeme {
                 token: Token::And,
                 index: 0,
-                length: 3
+                length: 3,
+                byte_index: 0,
+                byte_length: 3
             },
             Lexeme {
                 token: Token::Or,
                 index: 4,
-                length: 2
+                length: 2,
+                byte_index: 4,
+                byte_length: 2
             },
             Lexeme {
                 token: Token::Not,
                 index: 7,
-                length: 3
+                length: 3,
+                byte_index: 7,
+                byte_length: 3
             },
             Lexeme {
                 token: Token::Break,
                 index: 11,
-                length: 5
+                length: 5,
+                byte_index: 11,
+                byte_length: 5
             },
             Lexeme {
                 token: Token::Continue,
                 index: 17,
-                length: 8
+                length: 8,
+                byte_index: 17,
+                byte_length: 8
             },
         ]
     )
@@ -369,37 +488,51 @@ fn scanner_read_keywords_2() {
             Lexeme {
                 token: Token::True,
                 index: 0,
-                length: 4
+                length: 4,
+                byte_index: 0,
+                byte_length: 4
             },
             Lexeme {
                 token: Token::False,
                 index: 5,
-                length: 5
+                length: 5,
+                byte_index: 5,
+                byte_length: 5
             },
             Lexeme {
                 token: Token::If,
                 index: 11,
-                length: 2
+                length: 2,
+                byte_index: 11,
+                byte_length: 2
             },
             Lexeme {
                 token: Token::Else,
                 index: 14,
-                length: 4
+                length: 4,
+                byte_index: 14,
+                byte_length: 4
             },
             Lexeme {
                 token: Token::Null,
                 index: 19,
-                length: 4
+                length: 4,
+                byte_index: 19,
+                byte_length: 4
             },
             Lexeme {
                 token: Token::For,
                 index: 24,
-                length: 3
+                length: 3,
+                byte_index: 24,
+                byte_length: 3
             },
             Lexeme {
                 token: Token::Fn,
                 index: 28,
-                length: 2
+                length: 2,
+                byte_index: 28,
+                byte_length: 2
             },
         ]
     )
@@ -414,22 +547,30 @@ fn scanner_read_keywords_3() {
             Lexeme {
                 token: Token::Var,
                 index: 0,
-                length: 3
+                length: 3,
+                byte_index: 0,
+                byte_length: 3
             },
             Lexeme {
                 token: Token::Const,
                 index: 4,
-                length: 5
+                length: 5,
+                byte_index: 4,
+                byte_length: 5
             },
             Lexeme {
                 token: Token::Return,
                 index: 10,
-                length: 6
+                length: 6,
+                byte_index: 10,
+                byte_length: 6
             },
             Lexeme {
                 token: Token::While,
                 index: 17,
-                length: 5
+                length: 5,
+                byte_index: 17,
+                byte_length: 5
             },
         ]
     )
@@ -444,18 +585,583 @@ fn scanner_identifers_with_keyword_prefixes() {
             Lexeme {
                 token: Token::Identifier,
                 index: 0,
-                length: 8
+                length: 8,
+                byte_index: 0,
+                byte_length: 8
             },
             Lexeme {
                 token: Token::Identifier,
                 index: 9,
-                length: 7
+                length: 7,
+                byte_index: 9,
+                byte_length: 7
             },
             Lexeme {
                 token: Token::Identifier,
                 index: 17,
-                length: 2
+                length: 2,
+                byte_index: 17,
+                byte_length: 2
             },
         ]
     )
 }
+
+#[test]
+fn scanner_read_number_followed_by_punctuation() {
+    //................0123
+    assert_eq!(
+        Scanner::new("(1);").collect::<Vec<_>>(),
+        vec![
+            Lexeme {
+                token: Token::LeftParen,
+                index: 0,
+                length: 1,
+                byte_index: 0,
+                byte_length: 1
+            },
+            Lexeme {
+                token: Token::Int,
+                index: 1,
+                length: 1,
+                byte_index: 1,
+                byte_length: 1
+            },
+            Lexeme {
+                token: Token::RightParen,
+                index: 2,
+                length: 1,
+                byte_index: 2,
+                byte_length: 1
+            },
+            Lexeme {
+                token: Token::Semicolon,
+                index: 3,
+                length: 1,
+                byte_index: 3,
+                byte_length: 1
+            },
+        ]
+    )
+}
+
+#[test]
+fn scanner_identifiers_sharing_keyword_first_chars() {
+    //................012345678901234
+    assert_eq!(
+        Scanner::new("counter fx nil").collect::<Vec<_>>(),
+        vec![
+            Lexeme {
+                token: Token::Identifier,
+                index: 0,
+                length: 7,
+                byte_index: 0,
+                byte_length: 7
+            },
+            Lexeme {
+                token: Token::Identifier,
+                index: 8,
+                length: 2,
+                byte_index: 8,
+                byte_length: 2
+            },
+            Lexeme {
+                token: Token::Identifier,
+                index: 11,
+                length: 3,
+                byte_index: 11,
+                byte_length: 3
+            },
+        ]
+    )
+}
+
+#[test]
+fn scanner_tracks_byte_offsets_of_non_ascii_text() {
+    //................0 1234 56789
+    assert_eq!(
+        Scanner::new("\"héllo\" + x").collect::<Vec<_>>(),
+        vec![
+            Lexeme {
+                token: Token::String,
+                index: 0,
+                length: 7,
+                byte_index: 0,
+                byte_length: 8
+            },
+            Lexeme {
+                token: Token::Plus,
+                index: 8,
+                length: 1,
+                byte_index: 9,
+                byte_length: 1
+            },
+            Lexeme {
+                token: Token::Identifier,
+                index: 10,
+                length: 1,
+                byte_index: 11,
+                byte_length: 1
+            },
+        ]
+    )
+}
+
+#[test]
+fn scanner_read_string_with_escaped_quote() {
+    //...................0 1234 56 7
+    let source = r#""a\"b" x"#;
+    let lexemes = Scanner::new(source).collect::<Vec<_>>();
+
+    assert_eq!(
+        lexemes[0],
+        Lexeme {
+            token: Token::String,
+            index: 0,
+            length: 6,
+            byte_index: 0,
+            byte_length: 6
+        }
+    );
+    assert_eq!(lexemes[1].token, Token::Identifier);
+    assert_eq!(lexemes[0].string_value(source), Some("a\"b".to_string()));
+    assert_eq!(lexemes[1].string_value(source), None);
+}
+
+#[test]
+fn scanner_read_string_with_invalid_escape() {
+    assert_eq!(
+        Scanner::new(r#""a\qb";"#).collect::<Vec<_>>(),
+        vec![
+            Lexeme {
+                token: Token::Invalid(InvalidTokenReason::InvalidEscape),
+                index: 0,
+                length: 6,
+                byte_index: 0,
+                byte_length: 6
+            },
+            Lexeme {
+                token: Token::Semicolon,
+                index: 6,
+                length: 1,
+                byte_index: 6,
+                byte_length: 1
+            },
+        ]
+    )
+}
+
+#[test]
+fn scanner_read_string_ending_in_backslash_is_unterminated() {
+    assert_eq!(
+        Scanner::new(r#""abc\""#).collect::<Vec<_>>(),
+        vec![Lexeme {
+            token: Token::Invalid(InvalidTokenReason::UnterminatedString),
+            index: 0,
+            length: 6,
+            byte_index: 0,
+            byte_length: 6
+        }]
+    )
+}
+
+#[test]
+fn scanner_read_interpolated_string() {
+    //................0123456789012345678
+    let source = r#""a {b} c {d + 1}!""#;
+    let lexemes = Scanner::new(source).collect::<Vec<_>>();
+
+    assert_eq!(
+        lexemes.iter().map(|l| l.token).collect::<Vec<_>>(),
+        vec![
+            Token::StringStart,
+            Token::Identifier,
+            Token::StringMiddle,
+            Token::Identifier,
+            Token::Plus,
+            Token::Int,
+            Token::StringEnd,
+        ]
+    );
+    assert_eq!(
+        lexemes[2],
+        Lexeme {
+            token: Token::StringMiddle,
+            index: 5,
+            length: 5,
+            byte_index: 5,
+            byte_length: 5
+        }
+    );
+    assert_eq!(
+        lexemes
+            .iter()
+            .filter_map(|l| l.string_value(source))
+            .collect::<Vec<_>>(),
+        vec!["a ", " c ", "!"]
+    );
+}
+
+#[test]
+fn scanner_read_interpolation_with_nested_braces_and_strings() {
+    let tokens = Scanner::new(r#""x{f(fn () { return "{y}"; })}\{z\}""#)
+        .map(|l| l.token)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        tokens,
+        vec![
+            Token::StringStart,
+            Token::Identifier,
+            Token::LeftParen,
+            Token::Fn,
+            Token::LeftParen,
+            Token::RightParen,
+            Token::LeftBrace,
+            Token::Return,
+            Token::StringStart,
+            Token::Identifier,
+            Token::StringEnd,
+            Token::Semicolon,
+            Token::RightBrace,
+            Token::RightParen,
+            Token::StringEnd,
+        ]
+    );
+}
+
+#[test]
+fn scanner_read_string_ends_at_line_break() {
+    assert_eq!(
+        Scanner::new("\"one\ntwo").collect::<Vec<_>>(),
+        vec![
+            Lexeme {
+                token: Token::Invalid(InvalidTokenReason::UnterminatedString),
+                index: 0,
+                length: 4,
+                byte_index: 0,
+                byte_length: 4
+            },
+            Lexeme {
+                token: Token::Identifier,
+                index: 5,
+                length: 3,
+                byte_index: 5,
+                byte_length: 3
+            },
+        ]
+    )
+}
+
+#[test]
+fn scanner_read_raw_strings() {
+    let source = r##"r"C:\dir\{x}" r#"say "hi""# r#return"##;
+    let lexemes = Scanner::new(source).collect::<Vec<_>>();
+
+    assert_eq!(
+        lexemes.iter().map(|l| l.token).collect::<Vec<_>>(),
+        vec![
+            Token::RawString,
+            Token::RawString,
+            Token::Invalid(InvalidTokenReason::UnknownChars),
+        ]
+    );
+    assert_eq!(
+        lexemes[0].string_value(source),
+        Some(r"C:\dir\{x}".to_string())
+    );
+    assert_eq!(
+        lexemes[1].string_value(source),
+        Some(r#"say "hi""#.to_string())
+    );
+    assert_eq!(
+        Scanner::new("r#\"a\"").next().unwrap().token,
+        Token::Invalid(InvalidTokenReason::UnterminatedString)
+    );
+}
+
+#[test]
+fn scanner_read_multiline_string() {
+    let source = "var s = \"\"\"\n    hello \\\"\"\"\n      {world}\\t\n\n    \"\"\";";
+    let lexemes = Scanner::new(source).collect::<Vec<_>>();
+
+    assert_eq!(lexemes[3].token, Token::MultilineString);
+    assert_eq!(lexemes[4].token, Token::Semicolon);
+    assert_eq!(
+        lexemes[3].string_value(source),
+        Some("hello \"\"\"\n  {world}\t\n".to_string())
+    );
+    assert_eq!(
+        Scanner::new("\"\"\"abc\"\"").next().unwrap().token,
+        Token::Invalid(InvalidTokenReason::UnterminatedString)
+    );
+}
+
+/// Scans `source` into pairs of tokens and the text they were scanned from.
+fn tokens(source: &str) -> Vec<(Token, &str)> {
+    Scanner::new(source)
+        .with_text()
+        .map(|(l, text)| (l.token, text))
+        .collect()
+}
+
+#[test]
+fn scanner_read_number_literal_forms() {
+    assert_eq!(
+        tokens("0x1F 0o17 0b1010 0XfF_00 1_000_000 1.5e-3 2E+8 3e1_0 4. 0"),
+        vec![
+            (Token::Int, "0x1F"),
+            (Token::Int, "0o17"),
+            (Token::Int, "0b1010"),
+            (Token::Int, "0XfF_00"),
+            (Token::Int, "1_000_000"),
+            (Token::Float, "1.5e-3"),
+            (Token::Float, "2E+8"),
+            (Token::Float, "3e1_0"),
+            (Token::Float, "4."),
+            (Token::Int, "0"),
+        ]
+    );
+}
+
+#[test]
+fn scanner_read_malformed_number_literals() {
+    let invalid = Token::Invalid;
+
+    assert_eq!(
+        tokens("0b102 0o8 0x 0xg 1e 1e+; 1__0 1_ 0x_1 1_.5 .5 x.y"),
+        vec![
+            (invalid(InvalidTokenReason::InvalidDigit), "0b102"),
+            (invalid(InvalidTokenReason::InvalidDigit), "0o8"),
+            (invalid(InvalidTokenReason::MissingDigits), "0x"),
+            (invalid(InvalidTokenReason::UnknownNumberChars), "0xg"),
+            (invalid(InvalidTokenReason::MissingExponentDigits), "1e"),
+            (invalid(InvalidTokenReason::MissingExponentDigits), "1e+"),
+            (Token::Semicolon, ";"),
+            (invalid(InvalidTokenReason::InvalidDigitSeparator), "1__0"),
+            (invalid(InvalidTokenReason::InvalidDigitSeparator), "1_"),
+            (invalid(InvalidTokenReason::InvalidDigitSeparator), "0x_1"),
+            (invalid(InvalidTokenReason::InvalidDigitSeparator), "1_.5"),
+            (invalid(InvalidTokenReason::LeadingDot), ".5"),
+            (Token::Identifier, "x"),
+            (Token::Period, "."),
+            (Token::Identifier, "y"),
+        ]
+    );
+}
+
+#[test]
+fn scanner_number_values() {
+    let source = "0x7fffffffffffffff 0x8000000000000000 1_5.2_5 1e999 x";
+    let lexemes = Scanner::new(source).collect::<Vec<_>>();
+
+    assert_eq!(lexemes[0].int_value(source), Ok(i64::MAX));
+    assert_eq!(lexemes[1].int_value(source), Err(NumberError::OutOfRange));
+    assert_eq!(lexemes[2].float_value(source), Ok(15.25));
+    assert_eq!(lexemes[3].float_value(source), Err(NumberError::OutOfRange));
+    assert_eq!(lexemes[4].int_value(source), Err(NumberError::Invalid));
+    assert_eq!(lexemes[2].int_value(source), Err(NumberError::Invalid));
+}
+
+#[test]
+fn scanner_minus_is_never_part_of_a_number() {
+    assert_eq!(
+        tokens("a-1 a - -1 -(1)"),
+        vec![
+            (Token::Identifier, "a"),
+            (Token::Minus, "-"),
+            (Token::Int, "1"),
+            (Token::Identifier, "a"),
+            (Token::Minus, "-"),
+            (Token::Minus, "-"),
+            (Token::Int, "1"),
+            (Token::Minus, "-"),
+            (Token::LeftParen, "("),
+            (Token::Int, "1"),
+            (Token::RightParen, ")"),
+        ]
+    );
+}
+
+#[test]
+fn scanner_read_block_comments() {
+    assert_eq!(
+        tokens("a /* one /* two */ still one */ b /**/ c"),
+        vec![
+            (Token::Identifier, "a"),
+            (Token::BlockComment, "/* one /* two */ still one */"),
+            (Token::Identifier, "b"),
+            (Token::BlockComment, "/**/"),
+            (Token::Identifier, "c"),
+        ]
+    );
+    assert_eq!(
+        tokens("1 /* a\n/* b */\n"),
+        vec![
+            (Token::Int, "1"),
+            (
+                Token::Invalid(InvalidTokenReason::UnterminatedComment),
+                "/* a\n/* b */\n"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn scanner_read_doc_comments() {
+    let source = "/// Adds.\n//// not docs\n///\n// plain\nfn";
+    let lexemes = Scanner::new(source).collect::<Vec<_>>();
+
+    assert_eq!(
+        lexemes.iter().map(|l| l.token).collect::<Vec<_>>(),
+        vec![
+            Token::DocComment,
+            Token::Comment,
+            Token::DocComment,
+            Token::Comment,
+            Token::Fn,
+        ]
+    );
+    assert_eq!(lexemes[0].doc_text(source), Some("Adds."));
+    assert_eq!(lexemes[2].doc_text(source), Some(""));
+    assert_eq!(lexemes[1].doc_text(source), None);
+}
+
+#[test]
+fn scanner_groups_invalid_chars_into_one_lexeme() {
+    assert_eq!(
+        tokens("~~~~ 2pxyz+1 0b12z;@#a(x) !x"),
+        vec![
+            (Token::Invalid(InvalidTokenReason::UnknownChars), "~~~~"),
+            (
+                Token::Invalid(InvalidTokenReason::UnknownNumberChars),
+                "2pxyz"
+            ),
+            (Token::Plus, "+"),
+            (Token::Int, "1"),
+            (Token::Invalid(InvalidTokenReason::InvalidDigit), "0b12z"),
+            (Token::Semicolon, ";"),
+            (Token::Invalid(InvalidTokenReason::UnknownChars), "@#a"),
+            (Token::LeftParen, "("),
+            (Token::Identifier, "x"),
+            (Token::RightParen, ")"),
+            (Token::Invalid(InvalidTokenReason::BangNotSupported), "!"),
+            (Token::Identifier, "x"),
+        ]
+    );
+}
+
+#[test]
+fn scanner_ends_invalid_chars_at_colon() {
+    assert_eq!(
+        tokens("x#: int"),
+        vec![
+            (Token::Identifier, "x"),
+            (Token::Invalid(InvalidTokenReason::UnknownChars), "#"),
+            (Token::Colon, ":"),
+            (Token::Identifier, "int"),
+        ]
+    );
+}
+
+#[test]
+fn scanner_reads_unicode_identifiers() {
+    assert_eq!(
+        tokens("var café = 1; 猫_2 + _ñ; varé"),
+        vec![
+            (Token::Var, "var"),
+            (Token::Identifier, "café"),
+            (Token::Equal, "="),
+            (Token::Int, "1"),
+            (Token::Semicolon, ";"),
+            (Token::Identifier, "猫_2"),
+            (Token::Plus, "+"),
+            (Token::Identifier, "_ñ"),
+            (Token::Semicolon, ";"),
+            (Token::Identifier, "varé"),
+        ]
+    );
+
+    // Combining marks continue an identifier but cannot start one.
+    assert_eq!(
+        tokens("cafe\u{301} \u{301}x"),
+        vec![
+            (Token::Identifier, "cafe\u{301}"),
+            (Token::Invalid(InvalidTokenReason::UnknownChars), "\u{301}x"),
+        ]
+    );
+}
+
+#[test]
+fn lexeme_identifier_name_is_nfc() {
+    let source = "cafe\u{301} 1";
+    let lexemes: Vec<_> = Scanner::new(source).collect();
+
+    assert_eq!(lexemes[0].identifier_name(source), Some("café".to_string()));
+    assert_eq!(lexemes[1].identifier_name(source), None);
+}
+
+#[test]
+fn lexeme_text_borrows_from_source() {
+    let source = "var ねこ = \"🐈\"; // 猫";
+    let lexemes: Vec<_> = Scanner::new(source).collect();
+
+    assert_eq!(lexemes[1].text(source), "ねこ");
+    assert_eq!(lexemes[1].byte_range(), 4..10);
+    assert_eq!(lexemes[3].text(source), "\"🐈\"");
+    assert_eq!(lexemes[5].text(source), "// 猫");
+
+    let (lexeme, text) = Scanner::new(source).with_text().nth(3).unwrap();
+    assert_eq!(lexeme, lexemes[3]);
+    assert!(std::ptr::eq(text, &source[lexeme.byte_range()]));
+}
+
+#[test]
+fn token_display_uses_source_spelling() {
+    assert_eq!(Token::LeftBrace.to_string(), "`{`");
+    assert_eq!(Token::Arrow.to_string(), "`->`");
+    assert_eq!(Token::While.to_string(), "`while`");
+    assert_eq!(Token::Identifier.to_string(), "identifier");
+    assert_eq!(Token::StringEnd.to_string(), "end of string");
+    assert_eq!(
+        Token::Invalid(InvalidTokenReason::UnknownChars).to_string(),
+        "unknown characters"
+    );
+    assert_eq!(Token::Semicolon.spelling(), Some(";"));
+    assert_eq!(Token::Int.spelling(), None);
+}
+
+#[test]
+fn keyword_table_is_sorted_and_round_trips() {
+    assert!(KEYWORDS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+    for (name, token) in KEYWORDS {
+        assert_eq!(Token::from_keyword(name), Some(*token));
+        assert_eq!(token.keyword(), Some(*name));
+        assert!(token.is_keyword());
+        assert_eq!(tokens(name), vec![(*token, *name)]);
+    }
+
+    assert_eq!(Token::from_keyword("While"), None);
+    assert_eq!(Token::from_keyword("iff"), None);
+    assert_eq!(Token::Identifier.keyword(), None);
+    assert!(!Token::Plus.is_keyword());
+}
+
+#[test]
+fn scanner_keyword_prefixes_and_suffixes_are_identifiers() {
+    assert_eq!(
+        tokens("co con cons constant continued r rx fn_ _if"),
+        vec![
+            (Token::Identifier, "co"),
+            (Token::Identifier, "con"),
+            (Token::Identifier, "cons"),
+            (Token::Identifier, "constant"),
+            (Token::Identifier, "continued"),
+            (Token::Identifier, "r"),
+            (Token::Identifier, "rx"),
+            (Token::Identifier, "fn_"),
+            (Token::Identifier, "_if"),
+        ]
+    );
+}
@@ -0,0 +1,59 @@
+use kitty_lang_ast::span::Span;
+use kitty_lang_interpreter::source::{Location, SourceFile};
+
+fn loc(line: usize, column: usize) -> Location {
+    Location { line, column }
+}
+
+#[test]
+fn source_location_of_char_index() {
+    let source = SourceFile::new("test", "var x;\n\nprint(x);");
+
+    assert_eq!(source.location(0), loc(1, 1));
+    assert_eq!(source.location(4), loc(1, 5));
+    assert_eq!(source.location(6), loc(1, 7));
+    assert_eq!(source.location(7), loc(2, 1));
+    assert_eq!(source.location(8), loc(3, 1));
+    assert_eq!(source.location(14), loc(3, 7));
+    assert_eq!(source.location(100), loc(3, 10));
+}
+
+#[test]
+fn source_index_round_trips_location() {
+    let source = SourceFile::new("test", "ab\ncd\n");
+
+    for index in 0..=6 {
+        assert_eq!(source.index(source.location(index)), Some(index));
+    }
+
+    assert_eq!(source.index(loc(1, 4)), None);
+    assert_eq!(source.index(loc(4, 1)), None);
+    assert_eq!(source.index(loc(0, 1)), None);
+    assert_eq!(source.index(loc(1, 0)), None);
+}
+
+#[test]
+fn source_line_text_strips_line_endings() {
+    let source = SourceFile::new("test", "one\r\ntwo\nthree");
+
+    assert_eq!(source.line_count(), 3);
+    assert_eq!(source.line_text(1), Some("one"));
+    assert_eq!(source.line_text(2), Some("two"));
+    assert_eq!(source.line_text(3), Some("three"));
+    assert_eq!(source.line_text(0), None);
+    assert_eq!(source.line_text(4), None);
+    assert_eq!(source.location(5), loc(2, 1));
+}
+
+#[test]
+fn source_columns_count_chars_not_bytes() {
+    let source = SourceFile::new("test", "é\n\"日本\" x");
+
+    assert_eq!(source.location(6), loc(2, 5));
+    assert_eq!(source.byte_index(6), 11);
+    assert_eq!(source.location_of_byte(11), loc(2, 5));
+    assert_eq!(source.location_of_byte(5), loc(2, 2));
+    assert_eq!(source.location_of_byte(6), loc(2, 2));
+    assert_eq!(source.slice(Span::new(2, 4)), "\"日本\"");
+    assert_eq!(source.byte_index(100), source.text().len());
+}
@@ -0,0 +1,103 @@
+use kitty_lang_interpreter::{
+    scanner::{Lexeme, Scanner},
+    tokens::Token,
+    trivia::LosslessLexemes,
+};
+
+fn texts<'a>(source: &'a str, lexemes: &[Lexeme]) -> Vec<&'a str> {
+    lexemes.iter().map(|l| l.text(source)).collect()
+}
+
+#[test]
+fn scanner_trivia_mode_emits_whitespace_and_newlines() {
+    let source = "a \t=\r\n1;\r \n\n";
+
+    assert_eq!(
+        Scanner::new(source)
+            .with_trivia()
+            .with_text()
+            .map(|(l, text)| (l.token, text))
+            .collect::<Vec<_>>(),
+        vec![
+            (Token::Identifier, "a"),
+            (Token::Whitespace, " \t"),
+            (Token::Equal, "="),
+            (Token::Newline, "\r\n"),
+            (Token::Int, "1"),
+            (Token::Semicolon, ";"),
+            (Token::Whitespace, "\r "),
+            (Token::Newline, "\n"),
+            (Token::Newline, "\n"),
+        ]
+    );
+}
+
+#[test]
+fn scanner_skips_trivia_by_default() {
+    assert!(
+        Scanner::new(" a \n b\r\n").all(|l| !l.token.is_trivia() && l.token == Token::Identifier)
+    );
+}
+
+#[test]
+fn lossless_lexemes_attach_leading_and_trailing_trivia() {
+    let source = "var x = 1;  // one\n\n    print(x); \n";
+    let lossless = LosslessLexemes::scan(source);
+
+    let semicolon = &lossless.lexemes[4];
+    assert_eq!(semicolon.lexeme.text(source), ";");
+    assert_eq!(texts(source, &semicolon.leading), Vec::<&str>::new());
+    assert_eq!(texts(source, &semicolon.trailing), vec!["  "]);
+
+    let comment = &lossless.lexemes[5];
+    assert_eq!(comment.lexeme.token, Token::Comment);
+    assert_eq!(texts(source, &comment.trailing), Vec::<&str>::new());
+
+    let print = &lossless.lexemes[6];
+    assert_eq!(print.lexeme.text(source), "print");
+    assert_eq!(texts(source, &print.leading), vec!["\n", "\n", "    "]);
+
+    let last = lossless.lexemes.last().unwrap();
+    assert_eq!(last.lexeme.text(source), ";");
+    assert_eq!(texts(source, &last.trailing), vec![" "]);
+    assert_eq!(texts(source, &lossless.end), vec!["\n"]);
+}
+
+#[test]
+fn lossless_lexemes_without_tokens() {
+    assert_eq!(LosslessLexemes::scan(""), LosslessLexemes::default());
+
+    let source = "  \n\t";
+    let lossless = LosslessLexemes::scan(source);
+    assert!(lossless.lexemes.is_empty());
+    assert_eq!(texts(source, &lossless.end), vec!["  ", "\n", "\t"]);
+}
+
+#[test]
+fn lossless_lexemes_round_trip_source() {
+    let sources = [
+        "",
+        "var x = 1;",
+        "  fn f(a, b) {\r\n\treturn a + b;  \r\n}\r\n\r\n",
+        "/* block\n  comment */ var s = \"a {x}  b\";\n/// doc\nconst 猫 = \"\"\"\n  text\n  \"\"\";\n",
+        "~~ 0b12 \"unterminated\n  \r x\r",
+        "\n\n\n",
+    ];
+
+    for source in sources {
+        let lossless = LosslessLexemes::scan(source);
+        let text: String = lossless.iter().map(|l| l.text(source)).collect();
+        assert_eq!(text, source);
+
+        let positions: Vec<_> = lossless.iter().map(|l| l.byte_index).collect();
+        assert!(positions.is_sorted());
+        assert_eq!(
+            lossless
+                .iter()
+                .filter(|l| !l.token.is_trivia())
+                .cloned()
+                .collect::<Vec<_>>(),
+            Scanner::new(source).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use kitty_lang_ast::span::Span;
+use kitty_lang_interpreter::{
+    parser::Parser,
+    typeck::{Type, TypeChecker, TypeError, check},
+};
+
+fn check_source(source: &str) -> Result<(), Vec<TypeError>> {
+    check(&Parser::new(source).parse_program().unwrap())
+}
+
+#[test]
+fn typeck_accepts_untyped_programs() {
+    let sources = [
+        "var x = 1; x = \"now a string\"; print(x);",
+        "fn fib(n) { if (n < 2) return n; return fib(n - 1) + fib(n - 2); } print(fib(10));",
+        "var s = \"{1 + 2} items\"; const f = fn (a) { return a; }; f(s);",
+        "for (var i = 0; i < arg_count(); i = i + 1) print(arg(i));",
+    ];
+
+    for source in sources {
+        assert_eq!(check_source(source), Ok(()), "{source:?} should check");
+    }
+}
+
+#[test]
+fn typeck_accepts_well_typed_programs() {
+    let source = "\
+        var x: int = 1;
+        var y: float = 2.5 * x;
+        var z: float = x;
+        fn half(n: float) -> float { return n / 2.0; }
+        y = half(3) + half(y);
+        var h: fn(int) -> float = half;
+        fn greet(name: string, times: int) -> string { return \"hi {name} {times}\"; }
+        const message: string = greet(\"cat\", x + 1);
+        var g: fn(string, int) -> string = greet;
+        var maybe: null;
+        while (x < 3 and not false) x = x + 1;";
+
+    assert_eq!(check_source(source), Ok(()));
+}
+
+#[test]
+fn typeck_reports_mismatched_initializer() {
+    //................................0123456789012345
+    assert_eq!(
+        check_source("var x: int = \"one\";"),
+        Err(vec![TypeError::Mismatch {
+            expected: Type::Int,
+            found: Type::String,
+            span: Span::new(13, 5)
+        }])
+    );
+}
+
+#[test]
+fn typeck_reports_uninitialized_and_mismatched_assignments() {
+    //................................0123456789012345678
+    let errors = check_source("var x: int; x = 1.5;").unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            TypeError::Mismatch {
+                expected: Type::Int,
+                found: Type::Null,
+                span: Span::new(4, 1)
+            },
+            TypeError::Mismatch {
+                expected: Type::Int,
+                found: Type::Float,
+                span: Span::new(16, 3)
+            }
+        ]
+    );
+}
+
+#[test]
+fn typeck_reports_every_error() {
+    let errors = check_source("var a: bool = 1;\nvar b: string = true;\nif (1) {}").unwrap_err();
+
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0].to_string(), "expected bool but found int");
+    assert_eq!(errors[1].to_string(), "expected string but found bool");
+    assert_eq!(errors[2].to_string(), "expected bool but found int");
+}
+
+#[test]
+fn typeck_checks_parameters_and_return_types() {
+    //................................01234567890123456789012345678901234567
+    assert_eq!(
+        check_source("fn f(a: float) -> string { return a; }"),
+        Err(vec![TypeError::Mismatch {
+            expected: Type::String,
+            found: Type::Float,
+            span: Span::new(34, 1)
+        }])
+    );
+}
+
+#[test]
+fn typeck_reports_functions_that_can_end_without_returning() {
+    //................................0123456789012345678
+    assert_eq!(
+        check_source("fn f() -> int { }\nprint(f() + 1);"),
+        Err(vec![TypeError::Mismatch {
+            expected: Type::Int,
+            found: Type::Null,
+            span: Span::new(16, 1)
+        }])
+    );
+    assert!(check_source("fn f(a: bool) -> int { if (a) return 1; }").is_err());
+    assert!(check_source("fn f() -> int { while (true) { break; } }").is_err());
+
+    let sources = [
+        "fn f(a: bool) -> int { if (a) return 1; else { return 2; } }",
+        "fn f() -> int { while (true) { if (false) return 1; } }",
+        "fn f() -> int { for (;;) { while (true) break; } }",
+        "fn f() -> null { } fn g() { } var h = fn () -> any { };",
+    ];
+
+    for source in sources {
+        assert_eq!(check_source(source), Ok(()), "{source:?} should check");
+    }
+}
+
+#[test]
+fn typeck_checks_calls() {
+    let errors =
+        check_source("fn f(a: int) -> int { return a; }\nf(1, 2);\nf(\"x\");\n1(2);").unwrap_err();
+
+    assert!(matches!(
+        errors[0],
+        TypeError::ArityMismatch {
+            expected: 1,
+            found: 2,
+            ..
+        }
+    ));
+    assert!(matches!(
+        errors[1],
+        TypeError::Mismatch {
+            expected: Type::Int,
+            found: Type::String,
+            ..
+        }
+    ));
+    assert!(matches!(
+        errors[2],
+        TypeError::NotCallable {
+            found: Type::Int,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn typeck_checks_function_types() {
+    assert_eq!(
+        check_source("fn f(a: int) -> string { return \"\"; }\nvar g: fn(int) -> int = f;")
+            .unwrap_err()[0]
+            .to_string(),
+        "expected fn(int) -> int but found fn(int) -> string"
+    );
+    assert_eq!(
+        check_source("var g: fn(int) = fn (a) { return a; }; g(1);"),
+        Ok(())
+    );
+}
+
+#[test]
+fn typeck_checks_operators() {
+    let errors = check_source("var a = -\"x\";\nvar b = 1 + true;\nvar c = not 1;").unwrap_err();
+
+    assert_eq!(errors[0].to_string(), "cannot apply `-` to string");
+    assert_eq!(errors[1].to_string(), "cannot apply `+` to int and bool");
+    assert_eq!(errors[2].to_string(), "cannot apply `not` to int");
+}
+
+#[test]
+fn typeck_reports_unknown_types() {
+    //................................0123456789
+    assert_eq!(
+        check_source("var x: integer;"),
+        Err(vec![TypeError::UnknownType {
+            name: "integer".to_string(),
+            span: Span::new(7, 7)
+        }])
+    );
+}
+
+#[test]
+fn typeck_remembers_globals_between_checks() {
+    let mut checker = TypeChecker::new();
+
+    assert_eq!(
+        checker.check(&Parser::new("var x: int = 1;").parse_program().unwrap()),
+        Ok(())
+    );
+    assert_eq!(
+        checker.check_expression(&Parser::new("x + 1").parse_expression().unwrap()),
+        Ok(Type::Int)
+    );
+    assert!(
+        checker
+            .check(&Parser::new("x = \"one\";").parse_program().unwrap())
+            .is_err()
+    );
+}
+
+#[test]
+fn typeck_forgets_declarations_of_rejected_programs() {
+    let mut checker = TypeChecker::new();
+
+    assert!(
+        checker
+            .check(
+                &Parser::new("var n: int = 1; n = \"s\";")
+                    .parse_program()
+                    .unwrap()
+            )
+            .is_err()
+    );
+    assert_eq!(
+        checker.check(&Parser::new("n = \"s\";").parse_program().unwrap()),
+        Ok(())
+    );
+    assert_eq!(
+        checker.check_expression(&Parser::new("n + 1").parse_expression().unwrap()),
+        Ok(Type::Any)
+    );
+}